@@ -4,9 +4,14 @@ use std::fs::create_dir_all;
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
 use dummy_nfts_cw721::{
-    AllNftInfoResponse, ApprovedForAllResponse, ContractInfoResponse, ExecuteMsg,
-    HighestTokenIdResponse, InstantiateMsg, Metadata, MigrateMsg, MinterResponse, NftInfoResponse,
-    NumTokensResponse, OwnerOfResponse, QueryMsg, TokensResponse,
+    AllNftInfoResponse, ApprovalLimitResponse, ApprovalResponse, ApprovalsResponse,
+    ApprovedForAllResponse, BalanceResponse, BatchMintResponse, CanTransferResponse,
+    CheckRoyaltiesResponse, ContractInfoResponse, ExecuteMsg, FreezeStatusResponse,
+    HighestTokenIdResponse, InstantiateMsg, Metadata, MetadataLimitsResponse, MigrateMsg,
+    MintResponse, MinterResponse, NftInfoResponse, NumTokensResponse, OperatorGrantsResponse,
+    OwnerOfResponse, PauseStatusResponse, PendingMinterResponse, QueryMsg, RoleHoldersResponse,
+    RoyaltiesInfoResponse, SupplyInfoResponse, TokenRarityResponse, TokensResponse,
+    TopHoldersResponse, TraitSchemaResponse, TraitStatsResponse, TraitValuesResponse,
 };
 
 fn main() {
@@ -20,13 +25,34 @@ fn main() {
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
     export_schema(&schema_for!(AllNftInfoResponse), &out_dir);
+    export_schema(&schema_for!(ApprovalResponse), &out_dir);
+    export_schema(&schema_for!(ApprovalsResponse), &out_dir);
+    export_schema(&schema_for!(CanTransferResponse), &out_dir);
     export_schema(&schema_for!(ApprovedForAllResponse), &out_dir);
+    export_schema(&schema_for!(OperatorGrantsResponse), &out_dir);
     export_schema(&schema_for!(ContractInfoResponse), &out_dir);
     export_schema(&schema_for!(MinterResponse), &out_dir);
+    export_schema(&schema_for!(PendingMinterResponse), &out_dir);
+    export_schema(&schema_for!(RoleHoldersResponse), &out_dir);
     export_schema(&schema_for!(NftInfoResponse), &out_dir);
     export_schema(&schema_for!(NumTokensResponse), &out_dir);
+    export_schema(&schema_for!(BalanceResponse), &out_dir);
+    export_schema(&schema_for!(TopHoldersResponse), &out_dir);
     export_schema(&schema_for!(OwnerOfResponse), &out_dir);
     export_schema(&schema_for!(TokensResponse), &out_dir);
+    export_schema(&schema_for!(TraitValuesResponse), &out_dir);
+    export_schema(&schema_for!(TraitSchemaResponse), &out_dir);
+    export_schema(&schema_for!(TraitStatsResponse), &out_dir);
+    export_schema(&schema_for!(TokenRarityResponse), &out_dir);
     export_schema(&schema_for!(HighestTokenIdResponse), &out_dir);
+    export_schema(&schema_for!(SupplyInfoResponse), &out_dir);
+    export_schema(&schema_for!(FreezeStatusResponse), &out_dir);
+    export_schema(&schema_for!(PauseStatusResponse), &out_dir);
+    export_schema(&schema_for!(ApprovalLimitResponse), &out_dir);
+    export_schema(&schema_for!(RoyaltiesInfoResponse), &out_dir);
+    export_schema(&schema_for!(CheckRoyaltiesResponse), &out_dir);
     export_schema(&schema_for!(Metadata), &out_dir);
+    export_schema(&schema_for!(MetadataLimitsResponse), &out_dir);
+    export_schema(&schema_for!(MintResponse), &out_dir);
+    export_schema(&schema_for!(BatchMintResponse), &out_dir);
 }
@@ -1,9 +1,14 @@
-use cosmwasm_std::{Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response};
+use cosmwasm_std::{
+    to_binary, Addr, Binary, BlockInfo, ContractResult, Deps, DepsMut, Empty, Env, MessageInfo,
+    Order, Reply, Response, StdResult, SubMsg,
+};
 
-use crate::{Expiration, ReceiveMsg};
+use crate::{BatchReceiveMsg, Expiration, Metadata, ReceiveMsg, TransferAuthority};
 
-use crate::types::state::{Approval, DummyNftContract, TokenInfo};
-use crate::{ContractError, ExecuteMsg, MintMsg, TokenId};
+use crate::types::state::{
+    Approval, DummyNftContract, PauseScope, PendingMinter, PendingSend, Role, TokenInfo,
+};
+use crate::{BatchMintResponse, ContractError, ExecuteMsg, MintMsg, MintResponse, TokenId};
 
 impl<'a> DummyNftContract<'a> {
     pub fn execute(
@@ -13,8 +18,11 @@ impl<'a> DummyNftContract<'a> {
         info: MessageInfo,
         msg: ExecuteMsg,
     ) -> Result<Response<Empty>, ContractError> {
+        self.check_not_paused(deps.as_ref(), &msg)?;
+
         match msg {
             ExecuteMsg::Mint(msg) => self.mint(deps, env, info, *msg),
+            ExecuteMsg::BatchMint { mints } => self.batch_mint(deps, env, info, mints),
             ExecuteMsg::Approve {
                 spender,
                 token_id,
@@ -27,16 +35,69 @@ impl<'a> DummyNftContract<'a> {
                 self.approve_all(deps, env, info, operator, expires)
             }
             ExecuteMsg::RevokeAll { operator } => self.revoke_all(deps, env, info, operator),
+            ExecuteMsg::PruneExpired {
+                token_ids,
+                owners,
+                limit,
+            } => self.prune_expired(deps, env, info, token_ids, owners, limit),
             ExecuteMsg::TransferNft {
                 recipient,
                 token_id,
             } => self.transfer_nft(deps, env, info, recipient, token_id),
+            ExecuteMsg::BatchTransferNft {
+                recipient,
+                token_ids,
+            } => self.batch_transfer_nft(deps, env, info, recipient, token_ids),
+            ExecuteMsg::TransferNftMany { transfers } => {
+                self.transfer_nft_many(deps, env, info, transfers)
+            }
             ExecuteMsg::SendNft {
                 contract,
                 token_id,
                 msg,
-            } => self.send_nft(deps, env, info, contract, token_id, msg),
+                return_on_failure,
+            } => self.send_nft(
+                deps,
+                env,
+                info,
+                contract,
+                token_id,
+                msg,
+                return_on_failure.unwrap_or(false),
+            ),
+            ExecuteMsg::BatchSendNft {
+                contract,
+                token_ids,
+                msg,
+            } => self.batch_send_nft(deps, env, info, contract, token_ids, msg),
             ExecuteMsg::Burn { token_id } => self.burn(deps, env, info, token_id),
+            ExecuteMsg::UpdateMetadata {
+                token_id,
+                token_uri,
+                extension,
+            } => self.update_metadata(deps, env, info, token_id, token_uri, extension),
+            ExecuteMsg::FreezeMetadata { token_id } => {
+                self.freeze_metadata(deps, env, info, token_id)
+            }
+            ExecuteMsg::FreezeAllMetadata {} => self.freeze_all_metadata(deps, env, info),
+            ExecuteMsg::SetBaseUri {
+                base_uri,
+                token_uri_suffix,
+            } => self.set_base_uri(deps, env, info, base_uri, token_uri_suffix),
+            ExecuteMsg::ProposeMinter {
+                new_minter,
+                expires,
+            } => self.propose_minter(deps, env, info, new_minter, expires),
+            ExecuteMsg::AcceptMinter {} => self.accept_minter(deps, env, info),
+            ExecuteMsg::CancelMinterProposal {} => self.cancel_minter_proposal(deps, env, info),
+            ExecuteMsg::Pause { scope } => self.pause(deps, env, info, scope),
+            ExecuteMsg::Unpause {} => self.unpause(deps, env, info),
+            ExecuteMsg::GrantRole { role, address } => {
+                self.grant_role(deps, env, info, role, address)
+            }
+            ExecuteMsg::RevokeRole { role, address } => {
+                self.revoke_role(deps, env, info, role, address)
+            }
         }
     }
 }
@@ -45,44 +106,377 @@ impl<'a> DummyNftContract<'a> {
 impl<'a> DummyNftContract<'a> {
     pub fn mint(
         &self,
-        deps: DepsMut,
+        mut deps: DepsMut,
         _env: Env,
         info: MessageInfo,
         msg: MintMsg,
     ) -> Result<Response<Empty>, ContractError> {
-        let minter = self.minter.load(deps.storage)?;
+        self.check_minter(deps.as_ref(), &info)?;
+        self.check_supply(deps.as_ref(), 1)?;
 
-        if info.sender != minter {
-            return Err(ContractError::Unauthorized {});
+        let highest = self.highest_token_id.may_load(deps.storage)?;
+        let token_id = self._mint(deps.branch(), msg, highest)?;
+        self.increment_tokens(deps.storage)?;
+        self.update_highest(deps.storage, token_id)?;
+
+        Ok(Response::new()
+            .set_data(to_binary(&MintResponse { token_id })?)
+            .add_attribute("action", "mint")
+            .add_attribute("minter", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    pub fn batch_mint(
+        &self,
+        mut deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        mints: Vec<MintMsg>,
+    ) -> Result<Response<Empty>, ContractError> {
+        self.check_minter(deps.as_ref(), &info)?;
+        self.check_batch_size(deps.as_ref(), mints.len())?;
+        self.check_supply(deps.as_ref(), mints.len() as u64)?;
+
+        let mut res = Response::new()
+            .add_attribute("action", "batch_mint")
+            .add_attribute("minter", info.sender);
+        let mut highest = self.highest_token_id.may_load(deps.storage)?;
+        let mut token_ids = Vec::with_capacity(mints.len());
+        for (index, msg) in mints.into_iter().enumerate() {
+            let requested = msg.token_id;
+            let token_id = self._mint(deps.branch(), msg, highest).map_err(|err| {
+                ContractError::BatchEntry {
+                    index,
+                    token_id: requested,
+                    source: Box::new(err),
+                }
+            })?;
+            highest = highest.max(Some(token_id));
+            token_ids.push(token_id);
+            res = res.add_attribute("token_id", token_id);
         }
 
-        if self.burned.has(deps.storage, msg.token_id) {
-            return Err(ContractError::RemintBurned {
-                token_id: msg.token_id,
-            });
+        self.add_tokens(deps.storage, token_ids.len() as u64)?;
+        if let Some(highest) = highest {
+            self.update_highest(deps.storage, highest)?;
+        }
+
+        Ok(res.set_data(to_binary(&BatchMintResponse { token_ids })?))
+    }
+
+    // helpers
+
+    /// Creates a single token, without touching the token count or highest ID.
+    /// `highest` is the highest ID used so far, which is where auto-assigned IDs start from.
+    pub fn _mint(
+        &self,
+        deps: DepsMut,
+        msg: MintMsg,
+        highest: Option<TokenId>,
+    ) -> Result<TokenId, ContractError> {
+        let token_id = match msg.token_id {
+            Some(token_id) => token_id,
+            None => next_token_id(highest)?,
+        };
+        if let Some(max_token_id) = self.contract_info.load(deps.storage)?.max_token_id {
+            if token_id > max_token_id {
+                return Err(ContractError::TokenIdTooHigh {
+                    token_id,
+                    max_token_id,
+                });
+            }
+        }
+        if self.burned.has(deps.storage, token_id) {
+            return Err(ContractError::RemintBurned { token_id });
         }
+        let royalty = match msg.royalty {
+            Some(royalty) => Some(royalty.validate(deps.api)?),
+            None => None,
+        };
+
+        self.check_metadata(deps.storage, &msg.extension)?;
 
         // create the token
+        let owner = deps.api.addr_validate(&msg.owner)?;
         let token = TokenInfo {
-            owner: deps.api.addr_validate(&msg.owner)?,
+            owner: owner.clone(),
             approvals: vec![],
             token_uri: msg.token_uri,
             extension: msg.extension,
         };
-        self.tokens
-            .update(deps.storage, msg.token_id, |old| match old {
-                Some(_) => Err(ContractError::Claimed {}),
+        let token = self
+            .tokens
+            .update(deps.storage, token_id, |old| match old {
+                Some(_) => Err(ContractError::Claimed { token_id }),
                 None => Ok(token),
             })?;
+        self.index_traits(deps.storage, token_id, &token.extension)?;
+        self.increase_balance(deps.storage, &owner)?;
+        if let Some(royalty) = royalty {
+            self.token_royalties
+                .save(deps.storage, token_id, &royalty)?;
+        }
+        let transferable = match msg.transferable {
+            Some(transferable) => transferable,
+            None => self.collection_transferable(deps.storage)?,
+        };
+        if !transferable {
+            self.soulbound.save(deps.storage, token_id, &())?;
+        }
 
-        self.increment_tokens(deps.storage)?;
+        Ok(token_id)
+    }
+
+    /// returns an error if the message belongs to a paused operation
+    pub fn check_not_paused(&self, deps: Deps, msg: &ExecuteMsg) -> Result<(), ContractError> {
+        let scope = match msg {
+            ExecuteMsg::Mint(_) | ExecuteMsg::BatchMint { .. } => PauseScope::Mint,
+            ExecuteMsg::TransferNft { .. }
+            | ExecuteMsg::BatchTransferNft { .. }
+            | ExecuteMsg::TransferNftMany { .. }
+            | ExecuteMsg::SendNft { .. }
+            | ExecuteMsg::BatchSendNft { .. }
+            | ExecuteMsg::Burn { .. } => PauseScope::Transfer,
+            _ => return Ok(()),
+        };
+        if self.is_paused(deps.storage, scope)? {
+            return Err(ContractError::Paused {});
+        }
+        Ok(())
+    }
+
+    /// returns an error unless the sender holds the given role
+    pub fn check_role(
+        &self,
+        deps: Deps,
+        info: &MessageInfo,
+        role: Role,
+    ) -> Result<(), ContractError> {
+        if !self.has_role(deps.storage, role, &info.sender) {
+            return Err(ContractError::Unauthorized {});
+        }
+        Ok(())
+    }
+
+    /// returns an error unless the sender is allowed to mint
+    pub fn check_minter(&self, deps: Deps, info: &MessageInfo) -> Result<(), ContractError> {
+        self.check_role(deps, info, Role::Minter)
+    }
+
+    /// returns an error unless the sender is allowed to change token metadata
+    pub fn check_metadata_editor(
+        &self,
+        deps: Deps,
+        info: &MessageInfo,
+    ) -> Result<(), ContractError> {
+        if self.has_role(deps.storage, Role::Minter, &info.sender)
+            || self.has_role(deps.storage, Role::MetadataEditor, &info.sender)
+        {
+            Ok(())
+        } else {
+            Err(ContractError::Unauthorized {})
+        }
+    }
+
+    /// returns an error unless the sender is the primary minter and still holds the minter role
+    pub fn check_primary_minter(
+        &self,
+        deps: Deps,
+        info: &MessageInfo,
+    ) -> Result<(), ContractError> {
+        let minter = self.minter.load(deps.storage)?;
+        if info.sender != minter || !self.has_role(deps.storage, Role::Minter, &minter) {
+            return Err(ContractError::Unauthorized {});
+        }
+        Ok(())
+    }
+
+    /// returns an error if minting `amount` more tokens would exceed the maximum supply
+    pub fn check_supply(&self, deps: Deps, amount: u64) -> Result<(), ContractError> {
+        if let Some(max_supply) = self.contract_info.load(deps.storage)?.max_supply {
+            let minted = self.minted_count(deps.storage)?;
+            if minted.saturating_add(amount) > max_supply {
+                return Err(ContractError::MaxSupplyReached { max_supply });
+            }
+        }
+        Ok(())
+    }
+
+    /// returns an error if a batch is empty or larger than the configured maximum
+    pub fn check_batch_size(&self, deps: Deps, size: usize) -> Result<(), ContractError> {
+        if size == 0 {
+            return Err(ContractError::EmptyBatch {});
+        }
+        let max = self.max_batch_size(deps.storage)?;
+        if size > max as usize {
+            return Err(ContractError::BatchTooLarge { size, max });
+        }
+        Ok(())
+    }
+}
+
+impl<'a> DummyNftContract<'a> {
+    pub fn propose_minter(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        new_minter: String,
+        expires: Option<Expiration>,
+    ) -> Result<Response<Empty>, ContractError> {
+        self.check_primary_minter(deps.as_ref(), &info)?;
+
+        // reject expired data as invalid
+        let expires = expires.unwrap_or_default();
+        if expires.is_expired(&env.block) {
+            return Err(ContractError::Expired {});
+        }
 
-        self.update_highest(deps.storage, msg.token_id)?;
+        let pending = PendingMinter {
+            minter: deps.api.addr_validate(&new_minter)?,
+            expires,
+        };
+        self.pending_minter.save(deps.storage, &pending)?;
 
         Ok(Response::new()
-            .add_attribute("action", "mint")
-            .add_attribute("minter", info.sender)
-            .add_attribute("token_id", msg.token_id))
+            .add_attribute("action", "propose_minter")
+            .add_attribute("sender", info.sender)
+            .add_attribute("new_minter", new_minter))
+    }
+
+    pub fn accept_minter(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response<Empty>, ContractError> {
+        let pending = self
+            .pending_minter
+            .may_load(deps.storage)?
+            .ok_or(ContractError::NoPendingMinter {})?;
+        if pending.minter != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+        if pending.expires.is_expired(&env.block) {
+            return Err(ContractError::MinterProposalExpired {});
+        }
+
+        self.set_primary_minter(deps.storage, &pending.minter)?;
+        self.pending_minter.remove(deps.storage);
+
+        Ok(Response::new()
+            .add_attribute("action", "accept_minter")
+            .add_attribute("minter", info.sender))
+    }
+
+    pub fn cancel_minter_proposal(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+    ) -> Result<Response<Empty>, ContractError> {
+        self.check_primary_minter(deps.as_ref(), &info)?;
+        if self.pending_minter.may_load(deps.storage)?.is_none() {
+            return Err(ContractError::NoPendingMinter {});
+        }
+        self.pending_minter.remove(deps.storage);
+
+        Ok(Response::new()
+            .add_attribute("action", "cancel_minter_proposal")
+            .add_attribute("sender", info.sender))
+    }
+}
+
+impl<'a> DummyNftContract<'a> {
+    pub fn pause(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        scope: PauseScope,
+    ) -> Result<Response<Empty>, ContractError> {
+        self.check_role(deps.as_ref(), &info, Role::Pauser)?;
+
+        let scope = match self.paused.may_load(deps.storage)? {
+            Some(paused) => paused.merge(scope),
+            None => scope,
+        };
+        self.paused.save(deps.storage, &scope)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "pause")
+            .add_attribute("sender", info.sender)
+            .add_attribute(
+                "scope",
+                match scope {
+                    PauseScope::Mint => "mint",
+                    PauseScope::Transfer => "transfer",
+                    PauseScope::All => "all",
+                },
+            ))
+    }
+
+    pub fn unpause(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+    ) -> Result<Response<Empty>, ContractError> {
+        self.check_role(deps.as_ref(), &info, Role::Pauser)?;
+
+        self.paused.remove(deps.storage);
+
+        Ok(Response::new()
+            .add_attribute("action", "unpause")
+            .add_attribute("sender", info.sender))
+    }
+
+    pub fn grant_role(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        role: Role,
+        address: String,
+    ) -> Result<Response<Empty>, ContractError> {
+        self.check_role(deps.as_ref(), &info, Role::Admin)?;
+
+        let addr = deps.api.addr_validate(&address)?;
+        self.add_role(deps.storage, role, &addr)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "grant_role")
+            .add_attribute("sender", info.sender)
+            .add_attribute("role", role.as_str())
+            .add_attribute("address", address))
+    }
+
+    pub fn revoke_role(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        role: Role,
+        address: String,
+    ) -> Result<Response<Empty>, ContractError> {
+        self.check_role(deps.as_ref(), &info, Role::Admin)?;
+
+        let addr = deps.api.addr_validate(&address)?;
+        // keep at least the caller around so the contract cannot lose all its admins
+        if role == Role::Admin && addr == info.sender {
+            return Err(ContractError::CannotRevokeOwnAdmin {});
+        }
+        self.remove_role(deps.storage, role, &addr);
+        // a handoff proposed before the revocation must not hand the role back
+        if role == Role::Minter && addr == self.minter.load(deps.storage)? {
+            self.pending_minter.remove(deps.storage);
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "revoke_role")
+            .add_attribute("sender", info.sender)
+            .add_attribute("role", role.as_str())
+            .add_attribute("address", address))
     }
 }
 
@@ -95,7 +489,8 @@ impl<'a> DummyNftContract<'a> {
         recipient: String,
         token_id: TokenId,
     ) -> Result<Response<Empty>, ContractError> {
-        self._transfer_nft(deps, &env, &info, &recipient, token_id)?;
+        let recipient_addr = deps.api.addr_validate(&recipient)?;
+        self._transfer_nft(deps, &env, &info, &recipient_addr, token_id)?;
 
         Ok(Response::new()
             .add_attribute("action", "transfer_nft")
@@ -104,33 +499,157 @@ impl<'a> DummyNftContract<'a> {
             .add_attribute("token_id", token_id))
     }
 
+    pub fn batch_transfer_nft(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        recipient: String,
+        token_ids: Vec<TokenId>,
+    ) -> Result<Response<Empty>, ContractError> {
+        self.check_batch_size(deps.as_ref(), token_ids.len())?;
+        let recipient_addr = deps.api.addr_validate(&recipient)?;
+
+        let mut res = Response::new()
+            .add_attribute("action", "batch_transfer_nft")
+            .add_attribute("sender", info.sender.clone())
+            .add_attribute("recipient", recipient);
+        for token_id in token_ids {
+            self._transfer_nft(deps.branch(), &env, &info, &recipient_addr, token_id)?;
+            res = res.add_attribute("token_id", token_id);
+        }
+        Ok(res)
+    }
+
+    pub fn transfer_nft_many(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        transfers: Vec<(String, TokenId)>,
+    ) -> Result<Response<Empty>, ContractError> {
+        self.check_batch_size(deps.as_ref(), transfers.len())?;
+        let transfers = transfers
+            .into_iter()
+            .map(|(recipient, token_id)| Ok((deps.api.addr_validate(&recipient)?, token_id)))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        let mut res = Response::new()
+            .add_attribute("action", "transfer_nft_many")
+            .add_attribute("sender", info.sender.clone());
+        for (recipient, token_id) in transfers {
+            self._transfer_nft(deps.branch(), &env, &info, &recipient, token_id)?;
+            res = res
+                .add_attribute("recipient", recipient)
+                .add_attribute("token_id", token_id);
+        }
+        Ok(res)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn send_nft(
         &self,
-        deps: DepsMut,
+        mut deps: DepsMut,
         env: Env,
         info: MessageInfo,
         contract: String,
         token_id: TokenId,
         msg: Binary,
+        return_on_failure: bool,
     ) -> Result<Response<Empty>, ContractError> {
-        // Transfer token
-        self._transfer_nft(deps, &env, &info, &contract, token_id)?;
+        // Transfer token, remembering where it came from in case it has to go back
+        let contract_addr = deps.api.addr_validate(&contract)?;
+        let previous = self.tokens.load(deps.storage, token_id)?;
+        self._transfer_nft(deps.branch(), &env, &info, &contract_addr, token_id)?;
 
         let send = ReceiveMsg {
             sender: info.sender.to_string(),
             token_id,
             msg,
-        };
+        }
+        .into_cosmos_msg(contract.clone())?;
 
         // Send message
-        Ok(Response::new()
-            .add_message(send.into_cosmos_msg(contract.clone())?)
+        let res = if return_on_failure {
+            let pending = PendingSend {
+                owner: previous.owner,
+                approvals: previous.approvals,
+            };
+            self.pending_sends.save(deps.storage, token_id, &pending)?;
+            Response::new().add_submessage(SubMsg::reply_always(send, token_id.value()))
+        } else {
+            Response::new().add_message(send)
+        };
+        Ok(res
             .add_attribute("action", "send_nft")
             .add_attribute("sender", info.sender)
             .add_attribute("recipient", contract)
             .add_attribute("token_id", token_id))
     }
 
+    /// Handles the receiver's reply to a `SendNft` with `return_on_failure`,
+    /// the reply id is the ID of the token that was sent
+    pub fn reply(&self, deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+        let token_id = TokenId::new(msg.id);
+        match msg.result {
+            // a receiver that burned the token has already dropped its pending send
+            ContractResult::Ok(_) => {
+                self.pending_sends.remove(deps.storage, token_id);
+                Ok(Response::new())
+            }
+            ContractResult::Err(reason) => {
+                let pending = self.pending_sends.load(deps.storage, token_id)?;
+                self.pending_sends.remove(deps.storage, token_id);
+
+                // the receiver's own changes were reverted, so undo the transfer as well
+                let mut token = self.tokens.load(deps.storage, token_id)?;
+                self.decrease_balance(deps.storage, &token.owner)?;
+                self.increase_balance(deps.storage, &pending.owner)?;
+                token.owner = pending.owner;
+                token.approvals = pending.approvals;
+                self.tokens.save(deps.storage, token_id, &token)?;
+
+                Ok(Response::new()
+                    .add_attribute("action", "return_nft")
+                    .add_attribute("owner", token.owner)
+                    .add_attribute("token_id", token_id)
+                    .add_attribute("reason", reason))
+            }
+        }
+    }
+
+    pub fn batch_send_nft(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        contract: String,
+        token_ids: Vec<TokenId>,
+        msg: Binary,
+    ) -> Result<Response<Empty>, ContractError> {
+        self.check_batch_size(deps.as_ref(), token_ids.len())?;
+        let contract_addr = deps.api.addr_validate(&contract)?;
+
+        // Transfer tokens
+        let mut res = Response::new()
+            .add_attribute("action", "batch_send_nft")
+            .add_attribute("sender", info.sender.clone())
+            .add_attribute("recipient", contract.clone());
+        for &token_id in &token_ids {
+            self._transfer_nft(deps.branch(), &env, &info, &contract_addr, token_id)?;
+            res = res.add_attribute("token_id", token_id);
+        }
+
+        let send = BatchReceiveMsg {
+            sender: info.sender.to_string(),
+            token_ids,
+            msg,
+        };
+
+        // Send message
+        Ok(res.add_message(send.into_cosmos_msg(contract)?))
+    }
+
     pub fn approve(
         &self,
         deps: DepsMut,
@@ -182,8 +701,7 @@ impl<'a> DummyNftContract<'a> {
 
         // set the operator for us
         let operator_addr = deps.api.addr_validate(&operator)?;
-        self.operators
-            .save(deps.storage, (&info.sender, &operator_addr), &expires)?;
+        self.set_operator(deps.storage, &info.sender, &operator_addr, &expires)?;
 
         Ok(Response::new()
             .add_attribute("action", "approve_all")
@@ -199,8 +717,7 @@ impl<'a> DummyNftContract<'a> {
         operator: String,
     ) -> Result<Response<Empty>, ContractError> {
         let operator_addr = deps.api.addr_validate(&operator)?;
-        self.operators
-            .remove(deps.storage, (&info.sender, &operator_addr));
+        self.remove_operator(deps.storage, &info.sender, &operator_addr);
 
         Ok(Response::new()
             .add_attribute("action", "revoke_all")
@@ -208,6 +725,73 @@ impl<'a> DummyNftContract<'a> {
             .add_attribute("operator", operator))
     }
 
+    pub fn prune_expired(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_ids: Vec<TokenId>,
+        owners: Vec<String>,
+        limit: Option<u32>,
+    ) -> Result<Response<Empty>, ContractError> {
+        self.check_batch_size(deps.as_ref(), token_ids.len() + owners.len())?;
+        let max = self.max_batch_size(deps.storage)?;
+        let mut remaining = limit.unwrap_or(max).min(max) as usize;
+
+        let mut approvals_removed = 0;
+        for token_id in token_ids {
+            if remaining == 0 {
+                break;
+            }
+            // tokens may have been burned since the caller looked
+            let mut token = match self.tokens.may_load(deps.storage, token_id)? {
+                Some(token) => token,
+                None => continue,
+            };
+            let before = token.approvals.len();
+            let mut removed = 0;
+            token.approvals.retain(|apr| {
+                let prune = removed < remaining && apr.is_expired(&env.block);
+                if prune {
+                    removed += 1;
+                }
+                !prune
+            });
+            if token.approvals.len() != before {
+                self.tokens.save(deps.storage, token_id, &token)?;
+            }
+            approvals_removed += removed;
+            remaining -= removed;
+        }
+
+        let mut operators_removed = 0;
+        for owner in owners {
+            if remaining == 0 {
+                break;
+            }
+            let owner_addr = deps.api.addr_validate(&owner)?;
+            let expired: StdResult<Vec<_>> = self
+                .operators
+                .prefix(&owner_addr)
+                .range(deps.storage, None, None, Order::Ascending)
+                .filter(|r| r.is_err() || r.as_ref().unwrap().1.is_expired(&env.block))
+                .take(remaining)
+                .map(|r| r.and_then(|(k, _)| Ok(Addr::unchecked(String::from_utf8(k)?))))
+                .collect();
+            for operator in expired? {
+                self.remove_operator(deps.storage, &owner_addr, &operator);
+                operators_removed += 1;
+                remaining -= 1;
+            }
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "prune_expired")
+            .add_attribute("sender", info.sender)
+            .add_attribute("approvals_removed", approvals_removed.to_string())
+            .add_attribute("operators_removed", operators_removed.to_string()))
+    }
+
     pub fn burn(
         &self,
         deps: DepsMut,
@@ -216,9 +800,24 @@ impl<'a> DummyNftContract<'a> {
         token_id: TokenId,
     ) -> Result<Response<Empty>, ContractError> {
         let token = self.tokens.load(deps.storage, token_id)?;
-        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
+        // only the owner can give up a soulbound token
+        if !self.is_transferable(deps.storage, token_id) {
+            if info.sender != token.owner {
+                return Err(ContractError::Unauthorized {});
+            }
+        } else {
+            self.check_can_send(deps.as_ref(), &env, &info, &token)?;
+        }
 
         self.tokens.remove(deps.storage, token_id)?;
+        self.decrease_balance(deps.storage, &token.owner)?;
+        self.unindex_traits(deps.storage, token_id, &token.extension)?;
+        self.token_royalties.remove(deps.storage, token_id);
+        self.soulbound.remove(deps.storage, token_id);
+        // burned IDs can never be reminted, so the freeze has nothing left to protect
+        self.unfreeze_burned(deps.storage, &token, token_id)?;
+        // a receiver burning a token it was sent ends the send
+        self.pending_sends.remove(deps.storage, token_id);
         self.decrement_tokens(deps.storage)?;
         self.burned.save(deps.storage, token_id, &())?;
 
@@ -228,6 +827,115 @@ impl<'a> DummyNftContract<'a> {
             .add_attribute("token_id", token_id))
     }
 
+    pub fn update_metadata(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        token_id: TokenId,
+        token_uri: Option<String>,
+        extension: Option<Metadata>,
+    ) -> Result<Response<Empty>, ContractError> {
+        self.check_metadata_editor(deps.as_ref(), &info)?;
+
+        let mut token = self.tokens.load(deps.storage, token_id)?;
+        if self.is_metadata_frozen(deps.storage, token_id)? {
+            return Err(ContractError::MetadataFrozen { token_id });
+        }
+        let mut changed = vec![];
+        if let Some(token_uri) = token_uri {
+            if token.token_uri.as_ref() != Some(&token_uri) {
+                changed.push("token_uri");
+                token.token_uri = Some(token_uri);
+            }
+        }
+        if let Some(extension) = extension {
+            self.check_metadata(deps.storage, &extension)?;
+            changed.extend(token.extension.changed_fields(&extension));
+            if token.extension.attributes != extension.attributes {
+                self.unindex_traits(deps.storage, token_id, &token.extension)?;
+                self.index_traits(deps.storage, token_id, &extension)?;
+            }
+            token.extension = extension;
+        }
+        self.tokens.save(deps.storage, token_id, &token)?;
+
+        let changed = if changed.is_empty() {
+            "none".to_owned()
+        } else {
+            changed.join(",")
+        };
+        Ok(Response::new()
+            .add_attribute("action", "update_metadata")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_attribute("changed", changed))
+    }
+
+    pub fn freeze_metadata(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        token_id: TokenId,
+    ) -> Result<Response<Empty>, ContractError> {
+        self.check_metadata_editor(deps.as_ref(), &info)?;
+
+        // only existing tokens can be frozen
+        let token = self.tokens.load(deps.storage, token_id)?;
+        self.freeze_token(deps.storage, &token, token_id)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "freeze_metadata")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    pub fn freeze_all_metadata(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+    ) -> Result<Response<Empty>, ContractError> {
+        self.check_metadata_editor(deps.as_ref(), &info)?;
+
+        self.all_metadata_frozen.save(deps.storage, &true)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "freeze_all_metadata")
+            .add_attribute("sender", info.sender))
+    }
+
+    pub fn set_base_uri(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        base_uri: Option<String>,
+        token_uri_suffix: Option<String>,
+    ) -> Result<Response<Empty>, ContractError> {
+        self.check_minter(deps.as_ref(), &info)?;
+
+        // tokens without their own URI derive it from the base URI
+        if self.all_metadata_frozen(deps.storage)? || self.frozen_base_uri_tokens(deps.storage)? > 0
+        {
+            return Err(ContractError::BaseUriFrozen {});
+        }
+
+        let mut contract_info = self.contract_info.load(deps.storage)?;
+        contract_info.base_uri = base_uri;
+        contract_info.token_uri_suffix = token_uri_suffix;
+        self.contract_info.save(deps.storage, &contract_info)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "set_base_uri")
+            .add_attribute("sender", info.sender)
+            .add_attribute(
+                "base_uri",
+                contract_info.base_uri.unwrap_or_else(|| "none".to_owned()),
+            ))
+    }
+
     // helpers
 
     pub fn _transfer_nft(
@@ -235,14 +943,19 @@ impl<'a> DummyNftContract<'a> {
         deps: DepsMut,
         env: &Env,
         info: &MessageInfo,
-        recipient: &str,
+        recipient: &Addr,
         token_id: TokenId,
     ) -> Result<TokenInfo, ContractError> {
         let mut token = self.tokens.load(deps.storage, token_id)?;
         // ensure we have permissions
         self.check_can_send(deps.as_ref(), env, info, &token)?;
+        if !self.is_transferable(deps.storage, token_id) {
+            return Err(ContractError::NonTransferable { token_id });
+        }
         // set owner and remove existing approvals
-        token.owner = deps.api.addr_validate(recipient)?;
+        self.decrease_balance(deps.storage, &token.owner)?;
+        self.increase_balance(deps.storage, recipient)?;
+        token.owner = recipient.clone();
         token.approvals = vec![];
         self.tokens.save(deps.storage, token_id, &token)?;
         Ok(token)
@@ -264,21 +977,27 @@ impl<'a> DummyNftContract<'a> {
         // ensure we have permissions
         self.check_can_approve(deps.as_ref(), env, info, &token)?;
 
-        // update the approval list (remove any for the same spender before adding)
+        // update the approval list (remove any for the same spender before adding),
+        // dropping expired approvals while we are at it
         let spender_addr = deps.api.addr_validate(spender)?;
-        token.approvals = token
+        token
             .approvals
-            .into_iter()
-            .filter(|apr| apr.spender != spender_addr)
-            .collect();
+            .retain(|apr| apr.spender != spender_addr && !apr.is_expired(&env.block));
 
         // only difference between approve and revoke
         if add {
+            if !self.is_transferable(deps.storage, token_id) {
+                return Err(ContractError::NonTransferable { token_id });
+            }
             // reject expired data as invalid
             let expires = expires.unwrap_or_default();
             if expires.is_expired(&env.block) {
                 return Err(ContractError::Expired {});
             }
+            let max = self.max_approvals(deps.storage)?;
+            if token.approvals.len() >= max as usize {
+                return Err(ContractError::TooManyApprovals { max });
+            }
             let approval = Approval {
                 spender: spender_addr,
                 expires,
@@ -327,33 +1046,48 @@ impl<'a> DummyNftContract<'a> {
         info: &MessageInfo,
         token: &TokenInfo,
     ) -> Result<(), ContractError> {
+        match self.transfer_authority(deps, &env.block, token, &info.sender)? {
+            Some(_) => Ok(()),
+            None => Err(ContractError::Unauthorized {}),
+        }
+    }
+
+    /// returns why the sender can transfer ownership of the token, if it can
+    pub fn transfer_authority(
+        &self,
+        deps: Deps,
+        block: &BlockInfo,
+        token: &TokenInfo,
+        sender: &Addr,
+    ) -> StdResult<Option<TransferAuthority>> {
         // owner can send
-        if token.owner == info.sender {
-            return Ok(());
+        if token.owner == *sender {
+            return Ok(Some(TransferAuthority::Owner));
         }
 
         // any non-expired token approval can send
         if token
             .approvals
             .iter()
-            .any(|apr| apr.spender == info.sender && !apr.is_expired(&env.block))
+            .any(|apr| apr.spender == *sender && !apr.is_expired(block))
         {
-            return Ok(());
+            return Ok(Some(TransferAuthority::TokenApproval));
         }
 
         // operator can send
         let op = self
             .operators
-            .may_load(deps.storage, (&token.owner, &info.sender))?;
-        match op {
-            Some(ex) => {
-                if ex.is_expired(&env.block) {
-                    Err(ContractError::Unauthorized {})
-                } else {
-                    Ok(())
-                }
-            }
-            None => Err(ContractError::Unauthorized {}),
-        }
+            .may_load(deps.storage, (&token.owner, sender))?;
+        Ok(match op {
+            Some(ex) if !ex.is_expired(block) => Some(TransferAuthority::Operator),
+            _ => None,
+        })
     }
 }
+
+/// The ID after `highest`, which cannot have been minted or burned yet
+fn next_token_id(highest: Option<TokenId>) -> Result<TokenId, ContractError> {
+    highest
+        .map_or(Some(TokenId::new(1)), |highest| highest.next())
+        .ok_or(ContractError::TokenIdsExhausted {})
+}
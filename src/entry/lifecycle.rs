@@ -1,10 +1,14 @@
 //! Instantiating and migrating the contract.
-use cosmwasm_std::{DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult};
+use cosmwasm_std::{Addr, DepsMut, Empty, Env, MessageInfo, Order, Response, StdError, StdResult};
 
-use crate::{ContractInfoResponse, InstantiateMsg, MigrateMsg};
+use crate::{ContractError, ContractInfoResponse, InstantiateMsg, MigrateMsg, TokenId};
 use cw2::{get_contract_version, set_contract_version};
+use cw_storage_plus::U64Key;
 
-use crate::types::state::DummyNftContract;
+use crate::types::state::{
+    DummyNftContract, Role, BALANCE_INDEX_VERSION, OPERATOR_INDEX_VERSION, TOKEN_KEY_VERSION,
+    TRAIT_INDEX_VERSION,
+};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "dummy.finance/nfts";
@@ -17,19 +21,57 @@ impl<'a> DummyNftContract<'a> {
         _env: Env,
         _info: MessageInfo,
         msg: InstantiateMsg,
-    ) -> StdResult<Response<Empty>> {
+    ) -> Result<Response<Empty>, ContractError> {
         set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+        self.token_key_version
+            .save(deps.storage, &TOKEN_KEY_VERSION)?;
+        self.balance_index_version
+            .save(deps.storage, &BALANCE_INDEX_VERSION)?;
+        self.trait_index_version
+            .save(deps.storage, &TRAIT_INDEX_VERSION)?;
+        self.operator_index_version
+            .save(deps.storage, &OPERATOR_INDEX_VERSION)?;
 
         let info = ContractInfoResponse {
             name: msg.name,
             symbol: msg.symbol,
+            max_supply: msg.max_supply,
+            max_token_id: msg.max_token_id,
+            base_uri: msg.base_uri,
+            token_uri_suffix: msg.token_uri_suffix,
         };
         self.contract_info.save(deps.storage, &info)?;
         let minter = deps.api.addr_validate(&msg.minter)?;
-        self.minter.save(deps.storage, &minter)?;
+        self.set_primary_minter(deps.storage, &minter)?;
+        let admin = match &msg.admin {
+            None => minter,
+            Some(admin) => deps.api.addr_validate(admin)?,
+        };
+        self.add_role(deps.storage, Role::Admin, &admin)?;
+        if let Some(royalty) = msg.royalty {
+            let royalty = royalty.validate(deps.api)?;
+            self.default_royalty.save(deps.storage, &royalty)?;
+        }
+        if let Some(schema) = msg.trait_schema {
+            schema.check()?;
+            self.trait_schema.save(deps.storage, &schema)?;
+        }
+        if let Some(transferable) = msg.transferable {
+            self.transferable.save(deps.storage, &transferable)?;
+        }
+        if let Some(max_batch_size) = msg.max_batch_size {
+            self.max_batch_size.save(deps.storage, &max_batch_size)?;
+        }
+        if let Some(max_approvals) = msg.max_approvals {
+            self.max_approvals.save(deps.storage, &max_approvals)?;
+        }
+        if let Some(metadata_limits) = msg.metadata_limits {
+            self.metadata_limits.save(deps.storage, &metadata_limits)?;
+        }
         Ok(Response::default())
     }
 
+    /// Note that migrating never touches frozen metadata flags, so freezing stays permanent
     pub fn migrate(&self, deps: DepsMut, msg: MigrateMsg) -> StdResult<Response<Empty>> {
         let version = get_contract_version(deps.storage)?;
         if version.contract != CONTRACT_NAME {
@@ -43,6 +85,60 @@ impl<'a> DummyNftContract<'a> {
         };
 
         cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+        // Older versions stored token IDs little-endian, which does not sort numerically
+        self.migrate_token_keys(deps.storage)?;
+
+        // Older versions did not index traits
+        let trait_index_version = self.trait_index_version.may_load(deps.storage)?;
+        if trait_index_version.unwrap_or_default() < TRAIT_INDEX_VERSION {
+            let tokens: StdResult<Vec<_>> = self
+                .tokens
+                .range(deps.storage, None, None, Order::Ascending)
+                .collect();
+            for (key, token) in tokens? {
+                let token_id = TokenId::from_bytes(&key)?;
+                self.index_traits(deps.storage, token_id, &token.extension)?;
+            }
+            self.trait_index_version
+                .save(deps.storage, &TRAIT_INDEX_VERSION)?;
+        }
+
+        // Older versions did not track balances per owner, counting starts over from nothing
+        let balance_index_version = self.balance_index_version.may_load(deps.storage)?;
+        if balance_index_version.unwrap_or_default() < BALANCE_INDEX_VERSION {
+            let balances: StdResult<Vec<_>> = self
+                .balances
+                .range(deps.storage, None, None, Order::Ascending)
+                .collect();
+            for (owner, balance) in balances? {
+                let owner = Addr::unchecked(String::from_utf8(owner)?);
+                self.balances.remove(deps.storage, &owner);
+                self.holders
+                    .remove(deps.storage, (U64Key::new(balance), &owner));
+            }
+            let owners: StdResult<Vec<_>> = self
+                .tokens
+                .range(deps.storage, None, None, Order::Ascending)
+                .map(|item| item.map(|(_, token)| token.owner))
+                .collect();
+            for owner in owners? {
+                self.increase_balance(deps.storage, &owner)?;
+            }
+            self.balance_index_version
+                .save(deps.storage, &BALANCE_INDEX_VERSION)?;
+        }
+
+        // Older versions did not track how many tokens were ever minted
+        if self.minted_count.may_load(deps.storage)?.is_none() {
+            let burned = self
+                .burned
+                .keys(deps.storage, None, None, Order::Ascending)
+                .count() as u64;
+            let minted = self.token_count(deps.storage)? + burned;
+            self.minted_count.save(deps.storage, &minted)?;
+        }
+
         let mut info = self.contract_info(deps.as_ref())?;
         if let Some(name) = msg.name {
             info.name = name;
@@ -50,11 +146,96 @@ impl<'a> DummyNftContract<'a> {
         if let Some(symbol) = msg.symbol {
             info.symbol = symbol;
         }
+        if let Some(max_supply) = msg.max_supply {
+            if info.max_supply.is_some_and(|old| max_supply > old) {
+                return Err(StdError::generic_err("max_supply can only be lowered"));
+            }
+            if max_supply < self.minted_count(deps.storage)? {
+                return Err(StdError::generic_err(
+                    "max_supply cannot be below the number of tokens already minted",
+                ));
+            }
+            info.max_supply = Some(max_supply);
+        }
+        if let Some(max_token_id) = msg.max_token_id {
+            if info.max_token_id.is_some_and(|old| max_token_id > old) {
+                return Err(StdError::generic_err("max_token_id can only be lowered"));
+            }
+            if self
+                .highest_token_id
+                .may_load(deps.storage)?
+                .is_some_and(|highest| highest > max_token_id)
+            {
+                return Err(StdError::generic_err(
+                    "max_token_id cannot be below the highest token ID already minted",
+                ));
+            }
+            info.max_token_id = Some(max_token_id);
+        }
         self.contract_info.save(deps.storage, &info)?;
 
         if let Some(minter) = minter {
-            self.minter.save(deps.storage, &minter)?;
+            self.set_primary_minter(deps.storage, &minter)?;
+            // any handoff proposed by the previous minter no longer applies
+            self.pending_minter.remove(deps.storage);
+        }
+
+        // Older versions only knew a single minter, who becomes the admin
+        let has_admin = self
+            .roles
+            .prefix(Role::Admin.as_str())
+            .keys(deps.storage, None, None, Order::Ascending)
+            .next()
+            .is_some();
+        if !has_admin {
+            let minter = self.minter.load(deps.storage)?;
+            self.add_role(deps.storage, Role::Minter, &minter)?;
+            self.add_role(deps.storage, Role::Admin, &minter)?;
+        }
+        if let Some(max_batch_size) = msg.max_batch_size {
+            self.max_batch_size.save(deps.storage, &max_batch_size)?;
+        }
+        if let Some(max_approvals) = msg.max_approvals {
+            self.max_approvals.save(deps.storage, &max_approvals)?;
+        }
+
+        // Older versions did not index operators by operator
+        let operator_index_version = self.operator_index_version.may_load(deps.storage)?;
+        if operator_index_version.unwrap_or_default() < OPERATOR_INDEX_VERSION {
+            let grants: StdResult<Vec<_>> = self
+                .operators
+                .range(deps.storage, None, None, Order::Ascending)
+                .collect();
+            for (key, expires) in grants? {
+                let (granter, operator) = split_pair_key(&key)?;
+                self.operator_grants.save(
+                    deps.storage,
+                    (&Addr::unchecked(operator), &Addr::unchecked(granter)),
+                    &expires,
+                )?;
+            }
+            self.operator_index_version
+                .save(deps.storage, &OPERATOR_INDEX_VERSION)?;
         }
         Ok(Response::default())
     }
 }
+
+/// Splits a raw `(&Addr, &Addr)` key back into its two addresses.
+///
+/// cw-storage-plus 0.9 cannot deserialize composite keys, and granters are recorded nowhere
+/// but in `operators`, so iterating per granter with `prefix` would first need the granters
+/// parsed out of these keys anyway. The first address is length-prefixed, the second is not
+fn split_pair_key(key: &[u8]) -> StdResult<(String, String)> {
+    let corrupted = || StdError::generic_err("Corrupted operator key");
+    if key.len() < 2 {
+        return Err(corrupted());
+    }
+    let len = u16::from_be_bytes([key[0], key[1]]) as usize;
+    if key.len() < 2 + len {
+        return Err(corrupted());
+    }
+    let first = String::from_utf8(key[2..2 + len].to_vec())?;
+    let second = String::from_utf8(key[2 + len..].to_vec())?;
+    Ok((first, second))
+}
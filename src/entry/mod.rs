@@ -1,5 +1,5 @@
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult};
 
 use crate::*;
 
@@ -17,7 +17,7 @@ pub fn instantiate(
     env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let tract = DummyNftContract::default();
     tract.instantiate(deps, env, info, msg)
 }
@@ -39,6 +39,12 @@ pub fn execute(
     tract.execute(deps, env, info, msg)
 }
 
+#[entry_point]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let tract = DummyNftContract::default();
+    tract.reply(deps, env, msg)
+}
+
 #[entry_point]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     let tract = DummyNftContract::default();
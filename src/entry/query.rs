@@ -1,12 +1,20 @@
-use cosmwasm_std::{to_binary, Binary, BlockInfo, Deps, Env, Order, Pair, StdResult};
+use cosmwasm_std::{
+    to_binary, Binary, BlockInfo, Decimal, Deps, Env, Order, Pair, StdError, StdResult, Uint128,
+};
 use cw0::maybe_addr;
 
 use crate::{
-    AllNftInfoResponse, ApprovedForAllResponse, ContractInfoResponse, Expiration,
-    HighestTokenIdResponse, MinterResponse, NftInfoResponse, NumTokensResponse, OwnerOfResponse,
-    QueryMsg, TokenId, TokensResponse,
+    AllNftInfoResponse, ApprovalLimitResponse, ApprovalResponse, ApprovalsResponse,
+    ApprovedForAllResponse, BalanceResponse, CanTransferResponse, CheckRoyaltiesResponse,
+    ContractInfoResponse, Expiration, FreezeStatusResponse, HighestTokenIdResponse, Holder,
+    MetadataLimitsResponse, MinterResponse, NftInfoResponse, NumTokensResponse, OperatorGrant,
+    OperatorGrantsResponse, OrderBy, OwnerOfResponse, PauseScope, PauseStatusResponse,
+    PendingMinterResponse, QueryMsg, Role, RoleHoldersResponse, RoyaltiesInfoResponse,
+    SupplyInfoResponse, TokenId, TokenRarityResponse, TokensResponse, TopHoldersResponse,
+    TraitSchemaResponse, TraitStat, TraitStatsResponse, TraitValue, TraitValuesResponse,
+    TransferBlock,
 };
-use cw_storage_plus::Bound;
+use cw_storage_plus::{Bound, PrimaryKey, U64Key};
 
 use crate::types::state::{Approval, DummyNftContract, TokenInfo};
 
@@ -23,17 +31,129 @@ impl<'a> DummyNftContract<'a> {
         Ok(NumTokensResponse { count })
     }
 
+    pub fn balance_of(&self, deps: Deps, owner: String) -> StdResult<BalanceResponse> {
+        let owner_addr = deps.api.addr_validate(&owner)?;
+        let balance = self.balance(deps.storage, &owner_addr)?;
+        Ok(BalanceResponse { balance })
+    }
+
+    pub fn top_holders(
+        &self,
+        deps: Deps,
+        start_after: Option<(u64, String)>,
+        limit: Option<u32>,
+    ) -> StdResult<TopHoldersResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        // continue below the last row of the previous page, even if its balance changed since
+        let start = start_after
+            .map(|(balance, owner)| -> StdResult<_> {
+                let owner = deps.api.addr_validate(&owner)?;
+                Ok(Bound::exclusive(
+                    (U64Key::new(balance), &owner).joined_key(),
+                ))
+            })
+            .transpose()?;
+
+        let holders: StdResult<Vec<_>> = self
+            .holders
+            .keys(deps.storage, None, start, Order::Descending)
+            .take(limit)
+            .map(|k| parse_holder(&k))
+            .collect();
+        Ok(TopHoldersResponse { holders: holders? })
+    }
+
     pub fn highest_token_id(&self, deps: Deps) -> StdResult<HighestTokenIdResponse> {
         self.highest_token_id
             .may_load(deps.storage)
             .map(|highest_token_id| HighestTokenIdResponse { highest_token_id })
     }
 
+    pub fn supply_info(&self, deps: Deps) -> StdResult<SupplyInfoResponse> {
+        let info = self.contract_info.load(deps.storage)?;
+        let minted = self.minted_count(deps.storage)?;
+        Ok(SupplyInfoResponse {
+            max_supply: info.max_supply,
+            max_token_id: info.max_token_id,
+            minted,
+            remaining: info.max_supply.map(|max| max.saturating_sub(minted)),
+        })
+    }
+
+    pub fn approval_limit(&self, deps: Deps) -> StdResult<ApprovalLimitResponse> {
+        let max_approvals = self.max_approvals(deps.storage)?;
+        Ok(ApprovalLimitResponse { max_approvals })
+    }
+
+    pub fn metadata_limits_query(&self, deps: Deps) -> StdResult<MetadataLimitsResponse> {
+        let limits = self.metadata_limits(deps.storage)?;
+        Ok(MetadataLimitsResponse { limits })
+    }
+
+    pub fn pause_status(&self, deps: Deps) -> StdResult<PauseStatusResponse> {
+        Ok(PauseStatusResponse {
+            mint_paused: self.is_paused(deps.storage, PauseScope::Mint)?,
+            transfer_paused: self.is_paused(deps.storage, PauseScope::Transfer)?,
+        })
+    }
+
+    pub fn freeze_status(
+        &self,
+        deps: Deps,
+        token_id: Option<TokenId>,
+    ) -> StdResult<FreezeStatusResponse> {
+        let all_frozen = self.all_metadata_frozen(deps.storage)?;
+        let token_frozen = match token_id {
+            None => None,
+            Some(token_id) => {
+                // make sure we are not reporting on a token that does not exist
+                self.tokens.load(deps.storage, token_id)?;
+                Some(self.is_metadata_frozen(deps.storage, token_id)?)
+            }
+        };
+        Ok(FreezeStatusResponse {
+            all_frozen,
+            token_frozen,
+        })
+    }
+
+    pub fn royalty_info(
+        &self,
+        deps: Deps,
+        token_id: TokenId,
+        sale_price: Uint128,
+    ) -> StdResult<RoyaltiesInfoResponse> {
+        // make sure we are not reporting on a token that does not exist
+        self.tokens.load(deps.storage, token_id)?;
+        let royalty = match self.token_royalties.may_load(deps.storage, token_id)? {
+            Some(royalty) => Some(royalty),
+            None => self.default_royalty.may_load(deps.storage)?,
+        };
+        Ok(match royalty {
+            Some(royalty) => RoyaltiesInfoResponse {
+                address: royalty.payment_address.to_string(),
+                royalty_amount: sale_price * royalty.share,
+            },
+            None => RoyaltiesInfoResponse {
+                address: String::new(),
+                royalty_amount: Uint128::zero(),
+            },
+        })
+    }
+
+    pub fn check_royalties(&self, _deps: Deps) -> StdResult<CheckRoyaltiesResponse> {
+        Ok(CheckRoyaltiesResponse {
+            royalty_payments: true,
+        })
+    }
+
     pub fn nft_info(&self, deps: Deps, token_id: TokenId) -> StdResult<NftInfoResponse> {
         let info = self.tokens.load(deps.storage, token_id)?;
+        let contract_info = self.contract_info.load(deps.storage)?;
         Ok(NftInfoResponse {
-            token_uri: info.token_uri,
+            token_uri: contract_info.token_uri(token_id, info.token_uri),
             extension: info.extension,
+            transferable: self.is_transferable(deps.storage, token_id),
         })
     }
 
@@ -51,6 +171,70 @@ impl<'a> DummyNftContract<'a> {
         })
     }
 
+    pub fn approval(
+        &self,
+        deps: Deps,
+        env: Env,
+        token_id: TokenId,
+        spender: String,
+        include_expired: bool,
+    ) -> StdResult<ApprovalResponse> {
+        let token = self.tokens.load(deps.storage, token_id)?;
+
+        // token owner has absolute approval
+        if token.owner == spender {
+            let approval = crate::Approval {
+                spender: token.owner.to_string(),
+                expires: Expiration::Never {},
+            };
+            return Ok(ApprovalResponse { approval });
+        }
+
+        let approval = humanize_approvals(&env.block, &token, include_expired)
+            .into_iter()
+            .find(|apr| apr.spender == spender)
+            .ok_or_else(|| StdError::not_found("Approval"))?;
+        Ok(ApprovalResponse { approval })
+    }
+
+    pub fn approvals(
+        &self,
+        deps: Deps,
+        env: Env,
+        token_id: TokenId,
+        include_expired: bool,
+    ) -> StdResult<ApprovalsResponse> {
+        let token = self.tokens.load(deps.storage, token_id)?;
+        Ok(ApprovalsResponse {
+            approvals: humanize_approvals(&env.block, &token, include_expired),
+        })
+    }
+
+    pub fn can_transfer(
+        &self,
+        deps: Deps,
+        env: Env,
+        token_id: TokenId,
+        sender: String,
+    ) -> StdResult<CanTransferResponse> {
+        let token = self.tokens.load(deps.storage, token_id)?;
+        let sender_addr = deps.api.addr_validate(&sender)?;
+        let authority = self.transfer_authority(deps, &env.block, &token, &sender_addr)?;
+        let blocked_by = if self.is_paused(deps.storage, PauseScope::Transfer)? {
+            Some(TransferBlock::Paused)
+        } else if !self.is_transferable(deps.storage, token_id) {
+            Some(TransferBlock::NonTransferable)
+        } else {
+            None
+        };
+        Ok(CanTransferResponse {
+            can_transfer: authority.is_some() && blocked_by.is_none(),
+            authority,
+            blocked_by,
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn all_approvals(
         &self,
         deps: Deps,
@@ -59,16 +243,25 @@ impl<'a> DummyNftContract<'a> {
         include_expired: bool,
         start_after: Option<String>,
         limit: Option<u32>,
+        end: Option<String>,
+        inclusive: Option<bool>,
+        order: Option<OrderBy>,
     ) -> StdResult<ApprovedForAllResponse> {
         let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
         let start_addr = maybe_addr(deps.api, start_after)?;
-        let start = start_addr.map(|addr| Bound::exclusive(addr.as_ref()));
+        let end_addr = maybe_addr(deps.api, end)?;
+        let (min, max, order) = range_bounds(
+            start_addr.map(|addr| addr.as_bytes().to_vec()),
+            end_addr.map(|addr| addr.as_bytes().to_vec()),
+            inclusive,
+            order,
+        );
 
         let owner_addr = deps.api.addr_validate(&owner)?;
         let res: StdResult<Vec<_>> = self
             .operators
             .prefix(&owner_addr)
-            .range(deps.storage, start, None, Order::Ascending)
+            .range(deps.storage, min, max, order)
             .filter(|r| {
                 include_expired || r.is_err() || !r.as_ref().unwrap().1.is_expired(&env.block)
             })
@@ -78,15 +271,51 @@ impl<'a> DummyNftContract<'a> {
         Ok(ApprovedForAllResponse { operators: res? })
     }
 
+    pub fn operator_grants(
+        &self,
+        deps: Deps,
+        env: Env,
+        operator: String,
+        include_expired: bool,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<OperatorGrantsResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start_addr = maybe_addr(deps.api, start_after)?;
+        let start = start_addr.map(|addr| Bound::exclusive(addr.as_ref()));
+
+        let operator_addr = deps.api.addr_validate(&operator)?;
+        let res: StdResult<Vec<_>> = self
+            .operator_grants
+            .prefix(&operator_addr)
+            .range(deps.storage, start, None, Order::Ascending)
+            .filter(|r| {
+                include_expired || r.is_err() || !r.as_ref().unwrap().1.is_expired(&env.block)
+            })
+            .take(limit)
+            .map(parse_grant)
+            .collect();
+        Ok(OperatorGrantsResponse { grants: res? })
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn tokens(
         &self,
         deps: Deps,
         owner: String,
         start_after: Option<TokenId>,
         limit: Option<u32>,
+        end: Option<TokenId>,
+        inclusive: Option<bool>,
+        order: Option<OrderBy>,
     ) -> StdResult<TokensResponse> {
         let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-        let start = start_after.map(|token_id| Bound::exclusive(token_id.to_bytes()));
+        let (min, max, order) = range_bounds(
+            start_after.map(|token_id| token_id.to_bytes().to_vec()),
+            end.map(|token_id| token_id.to_bytes().to_vec()),
+            inclusive,
+            order,
+        );
 
         let owner_addr = deps.api.addr_validate(&owner)?;
         let pks: Vec<_> = self
@@ -94,7 +323,7 @@ impl<'a> DummyNftContract<'a> {
             .idx
             .owner
             .prefix(owner_addr)
-            .keys(deps.storage, start, None, Order::Ascending)
+            .keys(deps.storage, min, max, order)
             .take(limit)
             .collect();
 
@@ -107,19 +336,133 @@ impl<'a> DummyNftContract<'a> {
         deps: Deps,
         start_after: Option<TokenId>,
         limit: Option<u32>,
+        end: Option<TokenId>,
+        inclusive: Option<bool>,
+        order: Option<OrderBy>,
     ) -> StdResult<TokensResponse> {
         let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-        let start = start_after.map(|token_id| Bound::exclusive(token_id.to_bytes()));
+        let (min, max, order) = range_bounds(
+            start_after.map(|token_id| token_id.to_bytes().to_vec()),
+            end.map(|token_id| token_id.to_bytes().to_vec()),
+            inclusive,
+            order,
+        );
 
         let tokens: StdResult<Vec<TokenId>> = self
             .tokens
-            .range(deps.storage, start, None, Order::Ascending)
+            .range(deps.storage, min, max, order)
             .take(limit)
             .map(|item| item.and_then(|(k, _)| TokenId::from_bytes(&k)))
             .collect();
         Ok(TokensResponse { tokens: tokens? })
     }
 
+    pub fn tokens_by_trait(
+        &self,
+        deps: Deps,
+        trait_type: String,
+        value: String,
+        start_after: Option<TokenId>,
+        limit: Option<u32>,
+    ) -> StdResult<TokensResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(|token_id| Bound::exclusive(token_id.to_bytes()));
+
+        let tokens: StdResult<Vec<TokenId>> = self
+            .traits
+            .prefix((&trait_type, &value))
+            .keys(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|k| TokenId::from_bytes(&k))
+            .collect();
+        Ok(TokensResponse { tokens: tokens? })
+    }
+
+    pub fn trait_values(
+        &self,
+        deps: Deps,
+        trait_type: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<TraitValuesResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(|value| Bound::exclusive(value.as_bytes()));
+
+        let values: StdResult<Vec<_>> = self
+            .trait_counts
+            .prefix(&trait_type)
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| {
+                item.and_then(|(k, count)| {
+                    let value = String::from_utf8(k)?;
+                    Ok(TraitValue { value, count })
+                })
+            })
+            .collect();
+        Ok(TraitValuesResponse { values: values? })
+    }
+
+    pub fn trait_schema(&self, deps: Deps) -> StdResult<TraitSchemaResponse> {
+        let schema = self.trait_schema.may_load(deps.storage)?;
+        Ok(TraitSchemaResponse { schema })
+    }
+
+    pub fn trait_stats(
+        &self,
+        deps: Deps,
+        trait_type: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<TraitStatsResponse> {
+        let total_tokens = self.token_count(deps.storage)?;
+        let values = self.trait_values(deps, trait_type.clone(), start_after, limit)?;
+        Ok(TraitStatsResponse {
+            total_tokens,
+            values: values
+                .values
+                .into_iter()
+                .map(|v| TraitStat {
+                    trait_type: trait_type.clone(),
+                    frequency: frequency(v.count, total_tokens),
+                    value: v.value,
+                    count: v.count,
+                })
+                .collect(),
+        })
+    }
+
+    pub fn token_rarity(&self, deps: Deps, token_id: TokenId) -> StdResult<TokenRarityResponse> {
+        let token = self.tokens.load(deps.storage, token_id)?;
+        let total_tokens = self.token_count(deps.storage)?;
+
+        let mut traits: Vec<TraitStat> = vec![];
+        let mut rarity_score = Decimal::zero();
+        for attr in token.extension.attributes {
+            // the index lists a token once per trait, so do the same here
+            if traits
+                .iter()
+                .any(|t| t.trait_type == attr.trait_type && t.value == attr.value)
+            {
+                continue;
+            }
+            let count = self
+                .trait_counts
+                .load(deps.storage, (&attr.trait_type, &attr.value))?;
+            rarity_score = rarity_score + Decimal::from_ratio(total_tokens, count);
+            traits.push(TraitStat {
+                trait_type: attr.trait_type,
+                value: attr.value,
+                count,
+                frequency: frequency(count, total_tokens),
+            });
+        }
+        Ok(TokenRarityResponse {
+            traits,
+            rarity_score,
+        })
+    }
+
     pub fn all_nft_info(
         &self,
         deps: Deps,
@@ -128,14 +471,16 @@ impl<'a> DummyNftContract<'a> {
         include_expired: bool,
     ) -> StdResult<AllNftInfoResponse> {
         let info = self.tokens.load(deps.storage, token_id)?;
+        let contract_info = self.contract_info.load(deps.storage)?;
         Ok(AllNftInfoResponse {
             access: OwnerOfResponse {
                 owner: info.owner.to_string(),
                 approvals: humanize_approvals(&env.block, &info, include_expired),
             },
             info: NftInfoResponse {
-                token_uri: info.token_uri,
+                token_uri: contract_info.token_uri(token_id, info.token_uri),
                 extension: info.extension,
+                transferable: self.is_transferable(deps.storage, token_id),
             },
         })
     }
@@ -149,9 +494,44 @@ impl<'a> DummyNftContract<'a> {
         })
     }
 
+    pub fn pending_minter(&self, deps: Deps) -> StdResult<PendingMinterResponse> {
+        let pending = self.pending_minter.may_load(deps.storage)?;
+        Ok(PendingMinterResponse {
+            pending_minter: pending.as_ref().map(|p| p.minter.to_string()),
+            expires: pending.map(|p| p.expires),
+        })
+    }
+
+    pub fn role_holders(
+        &self,
+        deps: Deps,
+        role: Role,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<RoleHoldersResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start_addr = maybe_addr(deps.api, start_after)?;
+        let start = start_addr.map(|addr| Bound::exclusive(addr.as_ref()));
+
+        let holders: StdResult<Vec<_>> = self
+            .roles
+            .prefix(role.as_str())
+            .keys(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|k| String::from_utf8(k).map_err(StdError::from))
+            .collect();
+        Ok(RoleHoldersResponse { holders: holders? })
+    }
+
     pub fn query(&self, deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         match msg {
             QueryMsg::Minter {} => to_binary(&self.minter(deps)?),
+            QueryMsg::PendingMinter {} => to_binary(&self.pending_minter(deps)?),
+            QueryMsg::RoleHolders {
+                role,
+                start_after,
+                limit,
+            } => to_binary(&self.role_holders(deps, role, start_after, limit)?),
             QueryMsg::ContractInfo {} => to_binary(&self.contract_info(deps)?),
             QueryMsg::NftInfo { token_id } => to_binary(&self.nft_info(deps, token_id)?),
             QueryMsg::OwnerOf {
@@ -169,11 +549,34 @@ impl<'a> DummyNftContract<'a> {
                 token_id,
                 include_expired.unwrap_or(false),
             )?),
+            QueryMsg::Approval {
+                token_id,
+                spender,
+                include_expired,
+            } => to_binary(&self.approval(
+                deps,
+                env,
+                token_id,
+                spender,
+                include_expired.unwrap_or(false),
+            )?),
+            QueryMsg::Approvals {
+                token_id,
+                include_expired,
+            } => {
+                to_binary(&self.approvals(deps, env, token_id, include_expired.unwrap_or(false))?)
+            }
+            QueryMsg::CanTransfer { token_id, sender } => {
+                to_binary(&self.can_transfer(deps, env, token_id, sender)?)
+            }
             QueryMsg::ApprovedForAll {
                 owner,
                 include_expired,
                 start_after,
                 limit,
+                end,
+                inclusive,
+                order,
             } => to_binary(&self.all_approvals(
                 deps,
                 env,
@@ -181,19 +584,119 @@ impl<'a> DummyNftContract<'a> {
                 include_expired.unwrap_or(false),
                 start_after,
                 limit,
+                end,
+                inclusive,
+                order,
+            )?),
+            QueryMsg::OperatorGrants {
+                operator,
+                include_expired,
+                start_after,
+                limit,
+            } => to_binary(&self.operator_grants(
+                deps,
+                env,
+                operator,
+                include_expired.unwrap_or(false),
+                start_after,
+                limit,
             )?),
             QueryMsg::NumTokens {} => to_binary(&self.num_tokens(deps)?),
+            QueryMsg::Balance { owner } => to_binary(&self.balance_of(deps, owner)?),
+            QueryMsg::TopHolders { start_after, limit } => {
+                to_binary(&self.top_holders(deps, start_after, limit)?)
+            }
             QueryMsg::Tokens {
                 owner,
                 start_after,
                 limit,
-            } => to_binary(&self.tokens(deps, owner, start_after, limit)?),
-            QueryMsg::AllTokens { start_after, limit } => {
-                to_binary(&self.all_tokens(deps, start_after, limit)?)
-            }
+                end,
+                inclusive,
+                order,
+            } => to_binary(&self.tokens(deps, owner, start_after, limit, end, inclusive, order)?),
+            QueryMsg::AllTokens {
+                start_after,
+                limit,
+                end,
+                inclusive,
+                order,
+            } => to_binary(&self.all_tokens(deps, start_after, limit, end, inclusive, order)?),
+            QueryMsg::TokensByTrait {
+                trait_type,
+                value,
+                start_after,
+                limit,
+            } => to_binary(&self.tokens_by_trait(deps, trait_type, value, start_after, limit)?),
+            QueryMsg::TraitValues {
+                trait_type,
+                start_after,
+                limit,
+            } => to_binary(&self.trait_values(deps, trait_type, start_after, limit)?),
+            QueryMsg::TraitSchema {} => to_binary(&self.trait_schema(deps)?),
+            QueryMsg::TraitStats {
+                trait_type,
+                start_after,
+                limit,
+            } => to_binary(&self.trait_stats(deps, trait_type, start_after, limit)?),
+            QueryMsg::TokenRarity { token_id } => to_binary(&self.token_rarity(deps, token_id)?),
             QueryMsg::HighestTokenId {} => to_binary(&self.highest_token_id(deps)?),
+            QueryMsg::SupplyInfo {} => to_binary(&self.supply_info(deps)?),
+            QueryMsg::ApprovalLimit {} => to_binary(&self.approval_limit(deps)?),
+            QueryMsg::MetadataLimits {} => to_binary(&self.metadata_limits_query(deps)?),
+            QueryMsg::PauseStatus {} => to_binary(&self.pause_status(deps)?),
+            QueryMsg::RoyaltyInfo {
+                token_id,
+                sale_price,
+            } => to_binary(&self.royalty_info(deps, token_id, sale_price)?),
+            QueryMsg::CheckRoyalties {} => to_binary(&self.check_royalties(deps)?),
+            QueryMsg::FreezeStatus { token_id } => to_binary(&self.freeze_status(deps, token_id)?),
+        }
+    }
+}
+
+/// Turns the pagination arguments into range bounds, where `start_after` is the
+/// bound iteration starts from in the given order and `end` the one it stops at
+fn range_bounds(
+    start_after: Option<Vec<u8>>,
+    end: Option<Vec<u8>>,
+    inclusive: Option<bool>,
+    order: Option<OrderBy>,
+) -> (Option<Bound>, Option<Bound>, Order) {
+    let bound = |key: Vec<u8>| {
+        if inclusive.unwrap_or(false) {
+            Bound::Inclusive(key)
+        } else {
+            Bound::Exclusive(key)
         }
+    };
+    let (start, end) = (start_after.map(bound), end.map(bound));
+    let order = Order::from(order.unwrap_or(OrderBy::Ascending));
+    match order {
+        Order::Ascending => (start, end, order),
+        Order::Descending => (end, start, order),
+    }
+}
+
+/// Share of `total` that `count` makes up, zero for an empty collection
+fn frequency(count: u64, total: u64) -> Decimal {
+    if total == 0 {
+        Decimal::zero()
+    } else {
+        Decimal::from_ratio(count, total)
+    }
+}
+
+/// Splits a raw `(U64Key, &Addr)` holders key back into its parts
+fn parse_holder(key: &[u8]) -> StdResult<Holder> {
+    if key.len() < 10 {
+        return Err(StdError::generic_err("Corrupted holders key"));
     }
+    let mut balance = [0; 8];
+    balance.copy_from_slice(&key[2..10]);
+    Ok(Holder {
+        owner: String::from_utf8(key[10..].to_vec())?,
+        balance: u64::from_be_bytes(balance),
+    })
 }
 
 fn parse_approval(item: StdResult<Pair<Expiration>>) -> StdResult<crate::Approval> {
@@ -203,6 +706,13 @@ fn parse_approval(item: StdResult<Pair<Expiration>>) -> StdResult<crate::Approva
     })
 }
 
+fn parse_grant(item: StdResult<Pair<Expiration>>) -> StdResult<OperatorGrant> {
+    item.and_then(|(k, expires)| {
+        let owner = String::from_utf8(k)?;
+        Ok(OperatorGrant { owner, expires })
+    })
+}
+
 fn humanize_approvals(
     block: &BlockInfo,
     info: &TokenInfo,
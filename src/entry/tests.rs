@@ -1,13 +1,26 @@
 #![cfg(test)]
 use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-use cosmwasm_std::{from_binary, to_binary, CosmosMsg, DepsMut, Response, WasmMsg};
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, ContractResult, CosmosMsg, Decimal, Deps, DepsMut, Order, Reply,
+    Response, SubMsg, SubMsgExecutionResponse, Uint128, WasmMsg,
+};
 
 use crate::{
-    ApprovedForAllResponse, ContractInfoResponse, Expiration, HighestTokenIdResponse, Metadata,
-    MigrateMsg, NftInfoResponse, OwnerOfResponse, ReceiveMsg, TokenId, Trait,
+    ApprovalLimitResponse, ApprovalResponse, ApprovedForAllResponse, BalanceResponse,
+    BatchMintResponse, BatchReceiveMsg, CanTransferResponse, CheckRoyaltiesResponse,
+    ContractInfoResponse, Expiration, FreezeStatusResponse, HighestTokenIdResponse, Holder,
+    Metadata, MetadataLimits, MetadataLimitsResponse, MigrateMsg, MintResponse, NftInfoResponse,
+    OperatorGrant, OperatorGrantsResponse, OrderBy, OwnerOfResponse, PauseScope,
+    PauseStatusResponse, PendingMinterResponse, ReceiveMsg, Role, RoleHoldersResponse,
+    RoyaltiesInfoResponse, Royalty, SupplyInfoResponse, TokenId, TokenRarityResponse,
+    TokensResponse, Trait, TraitRule, TraitSchema, TraitStat, TraitStatsResponse, TraitValue,
+    TraitValuesResponse, TransferAuthority, TransferBlock, ValueRule,
 };
 
-use crate::{ContractError, ExecuteMsg, InstantiateMsg, DummyNftContract, MintMsg, QueryMsg};
+use crate::{ContractError, DummyNftContract, ExecuteMsg, InstantiateMsg, MintMsg, QueryMsg};
+
+use crate::types::state::{token_owner_idx, TokenIndexes, TokenInfo};
+use cw_storage_plus::{IndexedMap, Map, MultiIndex, U64Key};
 
 const MINTER: &str = "merlin";
 const CONTRACT_NAME: &str = "Magic Power";
@@ -19,6 +32,7 @@ fn setup_contract(deps: DepsMut<'_>) -> DummyNftContract<'static> {
         name: CONTRACT_NAME.to_string(),
         symbol: SYMBOL.to_string(),
         minter: String::from(MINTER),
+        ..Default::default()
     };
     let info = mock_info("creator", &[]);
     let res = contract.instantiate(deps, mock_env(), info, msg).unwrap();
@@ -35,6 +49,7 @@ fn proper_instantiation() {
         name: CONTRACT_NAME.to_string(),
         symbol: SYMBOL.to_string(),
         minter: String::from(MINTER),
+        ..Default::default()
     };
     let info = mock_info("creator", &[]);
 
@@ -53,6 +68,10 @@ fn proper_instantiation() {
         ContractInfoResponse {
             name: CONTRACT_NAME.to_string(),
             symbol: SYMBOL.to_string(),
+            max_supply: None,
+            max_token_id: None,
+            base_uri: None,
+            token_uri_suffix: None,
         }
     );
 
@@ -60,7 +79,9 @@ fn proper_instantiation() {
     assert_eq!(0, count.count);
 
     // list the token_ids
-    let tokens = contract.all_tokens(deps.as_ref(), None, None).unwrap();
+    let tokens = contract
+        .all_tokens(deps.as_ref(), None, None, None, None, None)
+        .unwrap();
     assert_eq!(0, tokens.tokens.len());
 }
 
@@ -73,10 +94,12 @@ fn minting() {
     let token_uri = "https://www.merriam-webster.com/dictionary/petrify".to_string();
 
     let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
-        token_id,
+        token_id: Some(token_id),
         owner: String::from("medusa"),
         token_uri: Some(token_uri.clone()),
         extension: Metadata::new_test(),
+        royalty: None,
+        transferable: None,
     }));
 
     // random cannot mint
@@ -102,18 +125,19 @@ fn minting() {
         .unwrap_err();
 
     // this nft info is correct
-    let info = contract.nft_info(deps.as_ref(), token_id.clone()).unwrap();
+    let info = contract.nft_info(deps.as_ref(), token_id).unwrap();
     assert_eq!(
         info,
         NftInfoResponse {
             token_uri: Some(token_uri),
             extension: Metadata::new_test(),
+            transferable: true,
         }
     );
 
     // owner info is correct
     let owner = contract
-        .owner_of(deps.as_ref(), mock_env(), token_id.clone(), true)
+        .owner_of(deps.as_ref(), mock_env(), token_id, true)
         .unwrap();
     assert_eq!(
         owner,
@@ -125,24 +149,415 @@ fn minting() {
 
     // Cannot mint same token_id again
     let mint_msg2 = ExecuteMsg::Mint(Box::new(MintMsg {
-        token_id: token_id.clone(),
+        token_id: Some(token_id),
         owner: String::from("hercules"),
         token_uri: None,
         extension: Metadata::new_test(),
+        royalty: None,
+        transferable: None,
     }));
 
     let allowed = mock_info(MINTER, &[]);
     let err = contract
         .execute(deps.as_mut(), mock_env(), allowed, mint_msg2)
         .unwrap_err();
-    assert_eq!(err, ContractError::Claimed {});
+    assert_eq!(err, ContractError::Claimed { token_id });
 
     // list the token_ids
-    let tokens = contract.all_tokens(deps.as_ref(), None, None).unwrap();
+    let tokens = contract
+        .all_tokens(deps.as_ref(), None, None, None, None, None)
+        .unwrap();
     assert_eq!(1, tokens.tokens.len());
     assert_eq!(vec![token_id], tokens.tokens);
 }
 
+#[test]
+fn batch_minting() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = DummyNftContract::default();
+    let msg = InstantiateMsg {
+        name: CONTRACT_NAME.to_string(),
+        symbol: SYMBOL.to_string(),
+        minter: String::from(MINTER),
+        max_batch_size: Some(3),
+        ..Default::default()
+    };
+    contract
+        .instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg)
+        .unwrap();
+
+    let mint = |token_id: u64| MintMsg {
+        token_id: Some(TokenId::new(token_id)),
+        owner: String::from("medusa"),
+        token_uri: None,
+        extension: Metadata::new_test(),
+        royalty: None,
+        transferable: None,
+    };
+    let allowed = mock_info(MINTER, &[]);
+
+    // random cannot mint
+    let batch_msg = ExecuteMsg::BatchMint {
+        mints: vec![mint(1), mint(7), mint(3)],
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            batch_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // empty and oversized batches are rejected
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            allowed.clone(),
+            ExecuteMsg::BatchMint { mints: vec![] },
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::EmptyBatch {});
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            allowed.clone(),
+            ExecuteMsg::BatchMint {
+                mints: vec![mint(10), mint(11), mint(12), mint(13)],
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::BatchTooLarge { size: 4, max: 3 });
+
+    // minter can mint
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), allowed.clone(), batch_msg)
+        .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .set_data(
+                to_binary(&BatchMintResponse {
+                    token_ids: vec![TokenId::new(1), TokenId::new(7), TokenId::new(3)]
+                })
+                .unwrap()
+            )
+            .add_attribute("action", "batch_mint")
+            .add_attribute("minter", MINTER)
+            .add_attribute("token_id", TokenId::new(1))
+            .add_attribute("token_id", TokenId::new(7))
+            .add_attribute("token_id", TokenId::new(3))
+    );
+    assert_eq!(3, contract.num_tokens(deps.as_ref()).unwrap().count);
+    assert_eq!(
+        contract.highest_token_id(deps.as_ref()).unwrap(),
+        HighestTokenIdResponse {
+            highest_token_id: Some(TokenId::new(7))
+        }
+    );
+
+    // a collision anywhere fails the whole batch and names the token
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            allowed.clone(),
+            ExecuteMsg::BatchMint {
+                mints: vec![mint(20), mint(7)],
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::BatchEntry {
+            index: 1,
+            token_id: Some(TokenId::new(7)),
+            source: Box::new(ContractError::Claimed {
+                token_id: TokenId::new(7)
+            }),
+        }
+    );
+
+    // duplicates within the batch are caught too
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            allowed.clone(),
+            ExecuteMsg::BatchMint {
+                mints: vec![mint(21), mint(21)],
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::BatchEntry {
+            index: 1,
+            token_id: Some(TokenId::new(21)),
+            source: Box::new(ContractError::Claimed {
+                token_id: TokenId::new(21)
+            }),
+        }
+    );
+
+    // so is any other invalid entry, such as a bad owner
+    let mut bad_owner = mint(23);
+    bad_owner.owner = String::from("x");
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            allowed,
+            ExecuteMsg::BatchMint {
+                mints: vec![mint(22), bad_owner],
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(
+        &err,
+        ContractError::BatchEntry {
+            index: 1,
+            token_id: Some(token_id),
+            source,
+        } if *token_id == TokenId::new(23) && matches!(**source, ContractError::Std(_))
+    ));
+    assert!(err.to_string().starts_with("Batch entry 1 failed: "));
+}
+
+#[test]
+fn auto_assigned_token_ids() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+    let allowed = mock_info(MINTER, &[]);
+
+    let mint = |token_id: Option<u64>| MintMsg {
+        token_id: token_id.map(TokenId::new),
+        owner: String::from("medusa"),
+        token_uri: None,
+        extension: Metadata::new_test(),
+        royalty: None,
+        transferable: None,
+    };
+
+    // first assigned ID is 1
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            allowed.clone(),
+            ExecuteMsg::Mint(Box::new(mint(None))),
+        )
+        .unwrap();
+    let token_id = TokenId::new(1);
+    assert_eq!(
+        res,
+        Response::new()
+            .set_data(to_binary(&MintResponse { token_id }).unwrap())
+            .add_attribute("action", "mint")
+            .add_attribute("minter", MINTER)
+            .add_attribute("token_id", token_id)
+    );
+
+    // explicit IDs move the counter forward
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            allowed.clone(),
+            ExecuteMsg::Mint(Box::new(mint(Some(3)))),
+        )
+        .unwrap();
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            allowed.clone(),
+            ExecuteMsg::Mint(Box::new(mint(None))),
+        )
+        .unwrap();
+    let minted: MintResponse = from_binary(&res.data.unwrap()).unwrap();
+    assert_eq!(minted.token_id, TokenId::new(4));
+
+    // burned IDs are never handed out again
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("medusa", &[]),
+            ExecuteMsg::Burn {
+                token_id: TokenId::new(4),
+            },
+        )
+        .unwrap();
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            allowed.clone(),
+            ExecuteMsg::Mint(Box::new(mint(None))),
+        )
+        .unwrap();
+    let minted: MintResponse = from_binary(&res.data.unwrap()).unwrap();
+    assert_eq!(minted.token_id, TokenId::new(5));
+
+    // batches assign consecutive IDs
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            allowed,
+            ExecuteMsg::BatchMint {
+                mints: vec![mint(None), mint(Some(9)), mint(None)],
+            },
+        )
+        .unwrap();
+    let minted: BatchMintResponse = from_binary(&res.data.unwrap()).unwrap();
+    assert_eq!(
+        minted.token_ids,
+        vec![TokenId::new(6), TokenId::new(9), TokenId::new(10)]
+    );
+    assert_eq!(
+        contract.highest_token_id(deps.as_ref()).unwrap(),
+        HighestTokenIdResponse {
+            highest_token_id: Some(TokenId::new(10))
+        }
+    );
+}
+
+#[test]
+fn supply_caps() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = DummyNftContract::default();
+    let msg = InstantiateMsg {
+        name: CONTRACT_NAME.to_string(),
+        symbol: SYMBOL.to_string(),
+        minter: String::from(MINTER),
+        max_supply: Some(3),
+        max_token_id: Some(TokenId::new(10)),
+        ..Default::default()
+    };
+    contract
+        .instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg)
+        .unwrap();
+    let info = contract.contract_info(deps.as_ref()).unwrap();
+    assert_eq!(info.max_supply, Some(3));
+    assert_eq!(info.max_token_id, Some(TokenId::new(10)));
+
+    let mint = |token_id: u64| MintMsg {
+        token_id: Some(TokenId::new(token_id)),
+        owner: MINTER.to_string(),
+        token_uri: None,
+        extension: Metadata::new_test(),
+        royalty: None,
+        transferable: None,
+    };
+    let allowed = mock_info(MINTER, &[]);
+
+    // IDs above the cap are rejected
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            allowed.clone(),
+            ExecuteMsg::Mint(Box::new(mint(11))),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::TokenIdTooHigh {
+            token_id: TokenId::new(11),
+            max_token_id: TokenId::new(10),
+        }
+    );
+
+    // burned tokens still count against the supply
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            allowed.clone(),
+            ExecuteMsg::Mint(Box::new(mint(1))),
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            allowed.clone(),
+            ExecuteMsg::Burn {
+                token_id: TokenId::new(1),
+            },
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            allowed.clone(),
+            ExecuteMsg::Mint(Box::new(mint(2))),
+        )
+        .unwrap();
+    assert_eq!(
+        contract.supply_info(deps.as_ref()).unwrap(),
+        SupplyInfoResponse {
+            max_supply: Some(3),
+            max_token_id: Some(TokenId::new(10)),
+            minted: 2,
+            remaining: Some(1),
+        }
+    );
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            allowed.clone(),
+            ExecuteMsg::BatchMint {
+                mints: vec![mint(3), mint(4)],
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::MaxSupplyReached { max_supply: 3 });
+
+    // caps can be lowered but never raised
+    let migrate_msg = |max_supply: Option<u64>, max_token_id: Option<u64>| MigrateMsg {
+        max_supply,
+        max_token_id: max_token_id.map(TokenId::new),
+        ..Default::default()
+    };
+    contract
+        .migrate(deps.as_mut(), migrate_msg(Some(4), None))
+        .unwrap_err();
+    contract
+        .migrate(deps.as_mut(), migrate_msg(None, Some(11)))
+        .unwrap_err();
+    // nor lowered below what has already been minted
+    contract
+        .migrate(deps.as_mut(), migrate_msg(Some(1), None))
+        .unwrap_err();
+    contract
+        .migrate(deps.as_mut(), migrate_msg(None, Some(1)))
+        .unwrap_err();
+    contract
+        .migrate(deps.as_mut(), migrate_msg(Some(2), Some(5)))
+        .unwrap();
+
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            allowed,
+            ExecuteMsg::Mint(Box::new(mint(3))),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::MaxSupplyReached { max_supply: 2 });
+    let info = contract.contract_info(deps.as_ref()).unwrap();
+    assert_eq!(info.max_supply, Some(2));
+    assert_eq!(info.max_token_id, Some(TokenId::new(5)));
+}
+
 #[test]
 fn burning() {
     let mut deps = mock_dependencies(&[]);
@@ -152,10 +567,12 @@ fn burning() {
     let token_uri = "https://www.merriam-webster.com/dictionary/petrify".to_string();
 
     let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
-        token_id,
+        token_id: Some(token_id),
         owner: MINTER.to_string(),
         token_uri: Some(token_uri),
         extension: Metadata::new_test(),
+        royalty: None,
+        transferable: None,
     }));
 
     let burn_msg = ExecuteMsg::Burn { token_id };
@@ -165,6 +582,15 @@ fn burning() {
     let _ = contract
         .execute(deps.as_mut(), mock_env(), allowed.clone(), mint_msg)
         .unwrap();
+    // frozen tokens can still be burned
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            allowed.clone(),
+            ExecuteMsg::FreezeMetadata { token_id },
+        )
+        .unwrap();
 
     // random not allowed to burn
     let random = mock_info("random", &[]);
@@ -184,9 +610,12 @@ fn burning() {
 
     // trying to get nft returns error
     let _ = contract.nft_info(deps.as_ref(), token_id).unwrap_err();
+    assert!(!contract.frozen_tokens.has(deps.as_ref().storage, token_id));
 
     // list the token_ids
-    let tokens = contract.all_tokens(deps.as_ref(), None, None).unwrap();
+    let tokens = contract
+        .all_tokens(deps.as_ref(), None, None, None, None, None)
+        .unwrap();
     assert!(tokens.tokens.is_empty());
 }
 
@@ -200,10 +629,12 @@ fn transferring_nft() {
     let token_uri = "https://www.merriam-webster.com/dictionary/melt".to_string();
 
     let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
-        token_id,
+        token_id: Some(token_id),
         owner: String::from("venus"),
         token_uri: Some(token_uri),
         extension: Metadata::new_test(),
+        royalty: None,
+        transferable: None,
     }));
 
     let minter = mock_info(MINTER, &[]);
@@ -215,7 +646,7 @@ fn transferring_nft() {
     let random = mock_info("random", &[]);
     let transfer_msg = ExecuteMsg::TransferNft {
         recipient: String::from("random"),
-        token_id: token_id.clone(),
+        token_id,
     };
 
     let err = contract
@@ -227,7 +658,7 @@ fn transferring_nft() {
     let random = mock_info("venus", &[]);
     let transfer_msg = ExecuteMsg::TransferNft {
         recipient: String::from("random"),
-        token_id: token_id.clone(),
+        token_id,
     };
 
     let res = contract
@@ -245,25 +676,142 @@ fn transferring_nft() {
 }
 
 #[test]
-fn sending_nft() {
+fn batch_transferring_nft() {
     let mut deps = mock_dependencies(&[]);
     let contract = setup_contract(deps.as_mut());
 
-    // Mint a token
-    let token_id = TokenId::new(202);
-    let token_uri = "https://www.merriam-webster.com/dictionary/melt".to_string();
-
-    let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
-        token_id,
-        owner: String::from("venus"),
-        token_uri: Some(token_uri),
-        extension: Metadata::new_test(),
-    }));
-
     let minter = mock_info(MINTER, &[]);
-    contract
-        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
-        .unwrap();
+    for token_id in 1..=3 {
+        let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
+            token_id: Some(TokenId::new(token_id)),
+            owner: String::from("venus"),
+            token_uri: None,
+            extension: Metadata::new_test(),
+            royalty: None,
+            transferable: None,
+        }));
+        contract
+            .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+            .unwrap();
+    }
+
+    // empty batches are rejected
+    let venus = mock_info("venus", &[]);
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            venus.clone(),
+            ExecuteMsg::BatchTransferNft {
+                recipient: String::from("mars"),
+                token_ids: vec![],
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::EmptyBatch {});
+
+    // owner can move several tokens at once
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            venus.clone(),
+            ExecuteMsg::BatchTransferNft {
+                recipient: String::from("mars"),
+                token_ids: vec![TokenId::new(1), TokenId::new(2)],
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "batch_transfer_nft")
+            .add_attribute("sender", "venus")
+            .add_attribute("recipient", "mars")
+            .add_attribute("token_id", TokenId::new(1))
+            .add_attribute("token_id", TokenId::new(2))
+    );
+    let tokens = contract
+        .tokens(
+            deps.as_ref(),
+            String::from("mars"),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    assert_eq!(tokens.tokens, vec![TokenId::new(1), TokenId::new(2)]);
+
+    // the whole batch fails if any token is not the sender's
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            venus.clone(),
+            ExecuteMsg::TransferNftMany {
+                transfers: vec![
+                    (String::from("jupiter"), TokenId::new(3)),
+                    (String::from("jupiter"), TokenId::new(1)),
+                ],
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // tokens can go to different recipients
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("mars", &[]),
+            ExecuteMsg::TransferNftMany {
+                transfers: vec![
+                    (String::from("jupiter"), TokenId::new(1)),
+                    (String::from("saturn"), TokenId::new(2)),
+                ],
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "transfer_nft_many")
+            .add_attribute("sender", "mars")
+            .add_attribute("recipient", "jupiter")
+            .add_attribute("token_id", TokenId::new(1))
+            .add_attribute("recipient", "saturn")
+            .add_attribute("token_id", TokenId::new(2))
+    );
+    let owner = contract
+        .owner_of(deps.as_ref(), mock_env(), TokenId::new(2), false)
+        .unwrap();
+    assert_eq!(owner.owner, "saturn");
+}
+
+#[test]
+fn sending_nft() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+
+    // Mint a token
+    let token_id = TokenId::new(202);
+    let token_uri = "https://www.merriam-webster.com/dictionary/melt".to_string();
+
+    let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
+        token_id: Some(token_id),
+        owner: String::from("venus"),
+        token_uri: Some(token_uri),
+        extension: Metadata::new_test(),
+        royalty: None,
+        transferable: None,
+    }));
+
+    let minter = mock_info(MINTER, &[]);
+    contract
+        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
+        .unwrap();
 
     let msg = to_binary("You now have the melting power").unwrap();
     let target = String::from("another_contract");
@@ -271,6 +819,7 @@ fn sending_nft() {
         contract: target.clone(),
         token_id,
         msg: msg.clone(),
+        return_on_failure: None,
     };
 
     let random = mock_info("random", &[]);
@@ -310,6 +859,186 @@ fn sending_nft() {
     );
 }
 
+#[test]
+fn sending_nft_with_return_on_failure() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+
+    let token_id = TokenId::new(7);
+    let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
+        token_id: Some(token_id),
+        owner: String::from("venus"),
+        token_uri: None,
+        extension: Metadata::new_test(),
+        royalty: None,
+        transferable: None,
+    }));
+    contract
+        .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), mint_msg)
+        .unwrap();
+    let venus = mock_info("venus", &[]);
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            venus.clone(),
+            ExecuteMsg::Approve {
+                spender: String::from("random"),
+                token_id,
+                expires: None,
+            },
+        )
+        .unwrap();
+    let approvals_before = contract
+        .owner_of(deps.as_ref(), mock_env(), token_id, true)
+        .unwrap()
+        .approvals;
+
+    let msg = to_binary("You now have the melting power").unwrap();
+    let target = String::from("another_contract");
+    let send_msg = ExecuteMsg::SendNft {
+        contract: target.clone(),
+        token_id,
+        msg: msg.clone(),
+        return_on_failure: Some(true),
+    };
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), venus.clone(), send_msg.clone())
+        .unwrap();
+
+    // the receive call is dispatched as a submessage replying with the token id
+    let payload = ReceiveMsg {
+        sender: String::from("venus"),
+        token_id,
+        msg,
+    };
+    let expected = payload.into_cosmos_msg(target.clone()).unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_submessage(SubMsg::reply_always(expected, 7))
+            .add_attribute("action", "send_nft")
+            .add_attribute("sender", "venus")
+            .add_attribute("recipient", "another_contract")
+            .add_attribute("token_id", token_id)
+    );
+
+    // when the receiver fails, the token and its approvals go back to the owner
+    let failure = Reply {
+        id: 7,
+        result: ContractResult::Err(String::from("not accepting tokens")),
+    };
+    let res = contract
+        .reply(deps.as_mut(), mock_env(), failure.clone())
+        .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "return_nft")
+            .add_attribute("owner", "venus")
+            .add_attribute("token_id", token_id)
+            .add_attribute("reason", "not accepting tokens")
+    );
+    let owner = contract
+        .owner_of(deps.as_ref(), mock_env(), token_id, true)
+        .unwrap();
+    assert_eq!(owner.owner, "venus");
+    assert_eq!(owner.approvals, approvals_before);
+
+    // a reply can only be handled once
+    contract
+        .reply(deps.as_mut(), mock_env(), failure)
+        .unwrap_err();
+
+    // when the receiver succeeds, the token stays where it was sent
+    contract
+        .execute(deps.as_mut(), mock_env(), venus, send_msg)
+        .unwrap();
+    let success = Reply {
+        id: 7,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: None,
+        }),
+    };
+    let res = contract.reply(deps.as_mut(), mock_env(), success).unwrap();
+    assert_eq!(res, Response::new());
+    let owner = contract
+        .owner_of(deps.as_ref(), mock_env(), token_id, true)
+        .unwrap();
+    assert_eq!(owner.owner, "another_contract");
+    assert!(owner.approvals.is_empty());
+}
+
+#[test]
+fn batch_sending_nft() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+
+    let minter = mock_info(MINTER, &[]);
+    for token_id in 1..=2 {
+        let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
+            token_id: Some(TokenId::new(token_id)),
+            owner: String::from("venus"),
+            token_uri: None,
+            extension: Metadata::new_test(),
+            royalty: None,
+            transferable: None,
+        }));
+        contract
+            .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+            .unwrap();
+    }
+
+    let msg = to_binary("Stake them all").unwrap();
+    let target = String::from("staking_contract");
+    let token_ids = vec![TokenId::new(1), TokenId::new(2)];
+    let send_msg = ExecuteMsg::BatchSendNft {
+        contract: target.clone(),
+        token_ids: token_ids.clone(),
+        msg: msg.clone(),
+    };
+
+    let random = mock_info("random", &[]);
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), random, send_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // but owner can, and the receiver is called once
+    let venus = mock_info("venus", &[]);
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), venus, send_msg)
+        .unwrap();
+
+    let payload = BatchReceiveMsg {
+        sender: String::from("venus"),
+        token_ids,
+        msg,
+    };
+    let expected = payload.into_cosmos_msg(target.clone()).unwrap();
+    match &expected {
+        CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) => {
+            assert_eq!(contract_addr, &target)
+        }
+        m => panic!("Unexpected message type: {:?}", m),
+    }
+    assert_eq!(
+        res,
+        Response::new()
+            .add_message(expected)
+            .add_attribute("action", "batch_send_nft")
+            .add_attribute("sender", "venus")
+            .add_attribute("recipient", "staking_contract")
+            .add_attribute("token_id", TokenId::new(1))
+            .add_attribute("token_id", TokenId::new(2))
+    );
+    let tokens = contract
+        .tokens(deps.as_ref(), target, None, None, None, None, None)
+        .unwrap();
+    assert_eq!(tokens.tokens, vec![TokenId::new(1), TokenId::new(2)]);
+}
+
 #[test]
 fn approving_revoking() {
     let mut deps = mock_dependencies(&[]);
@@ -320,10 +1049,12 @@ fn approving_revoking() {
     let token_uri = "https://www.merriam-webster.com/dictionary/grow".to_string();
 
     let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
-        token_id,
+        token_id: Some(token_id),
         owner: String::from("demeter"),
         token_uri: Some(token_uri),
         extension: Metadata::new_test(),
+        royalty: None,
+        transferable: None,
     }));
 
     let minter = mock_info(MINTER, &[]);
@@ -347,14 +1078,14 @@ fn approving_revoking() {
             .add_attribute("action", "approve")
             .add_attribute("sender", "demeter")
             .add_attribute("spender", "random")
-            .add_attribute("token_id", token_id.clone())
+            .add_attribute("token_id", token_id)
     );
 
     // random can now transfer
     let random = mock_info("random", &[]);
     let transfer_msg = ExecuteMsg::TransferNft {
         recipient: String::from("person"),
-        token_id: token_id.clone(),
+        token_id,
     };
     contract
         .execute(deps.as_mut(), mock_env(), random, transfer_msg)
@@ -362,7 +1093,7 @@ fn approving_revoking() {
 
     // Approvals are removed / cleared
     let query_msg = QueryMsg::OwnerOf {
-        token_id: token_id.clone(),
+        token_id,
         include_expired: None,
     };
     let res: OwnerOfResponse = from_binary(
@@ -382,7 +1113,7 @@ fn approving_revoking() {
     // Approve, revoke, and check for empty, to test revoke
     let approve_msg = ExecuteMsg::Approve {
         spender: String::from("random"),
-        token_id: token_id.clone(),
+        token_id,
         expires: None,
     };
     let owner = mock_info("person", &[]);
@@ -427,10 +1158,12 @@ fn approving_all_revoking_all() {
     let token_uri2 = "https://www.merriam-webster.com/dictionary/grow2".to_string();
 
     let mint_msg1 = ExecuteMsg::Mint(Box::new(MintMsg {
-        token_id: token_id1,
+        token_id: Some(token_id1),
         owner: String::from("demeter"),
         token_uri: Some(token_uri1),
         extension: Metadata::new_test(),
+        royalty: None,
+        transferable: None,
     }));
 
     let minter = mock_info(MINTER, &[]);
@@ -439,10 +1172,12 @@ fn approving_all_revoking_all() {
         .unwrap();
 
     let mint_msg2 = ExecuteMsg::Mint(Box::new(MintMsg {
-        token_id: token_id2.clone(),
+        token_id: Some(token_id2),
         owner: String::from("demeter"),
         token_uri: Some(token_uri2),
         extension: Metadata::new_test(),
+        royalty: None,
+        transferable: None,
     }));
 
     contract
@@ -450,14 +1185,16 @@ fn approving_all_revoking_all() {
         .unwrap();
 
     // paginate the token_ids
-    let tokens = contract.all_tokens(deps.as_ref(), None, Some(1)).unwrap();
+    let tokens = contract
+        .all_tokens(deps.as_ref(), None, Some(1), None, None, None)
+        .unwrap();
     assert_eq!(1, tokens.tokens.len());
-    assert_eq!(vec![token_id1.clone()], tokens.tokens);
+    assert_eq!(vec![token_id1], tokens.tokens);
     let tokens = contract
-        .all_tokens(deps.as_ref(), Some(token_id1), Some(3))
+        .all_tokens(deps.as_ref(), Some(token_id1), Some(3), None, None, None)
         .unwrap();
     assert_eq!(1, tokens.tokens.len());
-    assert_eq!(vec![token_id2.clone()], tokens.tokens);
+    assert_eq!(vec![token_id2], tokens.tokens);
 
     // demeter gives random full (operator) power over her tokens
     let approve_all_msg = ExecuteMsg::ApproveAll {
@@ -498,6 +1235,7 @@ fn approving_all_revoking_all() {
         contract: String::from("another_contract"),
         token_id: token_id2,
         msg: to_binary(&msg).unwrap(),
+        return_on_failure: None,
     };
     contract
         .execute(deps.as_mut(), mock_env(), random, send_msg)
@@ -522,6 +1260,9 @@ fn approving_all_revoking_all() {
             true,
             None,
             None,
+            None,
+            None,
+            None,
         )
         .unwrap();
     assert_eq!(
@@ -554,6 +1295,9 @@ fn approving_all_revoking_all() {
             true,
             None,
             Some(1),
+            None,
+            None,
+            None,
         )
         .unwrap();
     assert_eq!(
@@ -573,6 +1317,9 @@ fn approving_all_revoking_all() {
             true,
             Some(String::from("buddy")),
             Some(2),
+            None,
+            None,
+            None,
         )
         .unwrap();
     assert_eq!(
@@ -601,6 +1348,9 @@ fn approving_all_revoking_all() {
             false,
             None,
             None,
+            None,
+            None,
+            None,
         )
         .unwrap();
     assert_eq!(
@@ -624,245 +1374,3023 @@ fn approving_all_revoking_all() {
             false,
             None,
             None,
+            None,
+            None,
+            None,
         )
         .unwrap();
     assert_eq!(0, res.operators.len());
 }
 
 #[test]
-fn query_tokens_by_owner() {
+fn querying_approvals() {
     let mut deps = mock_dependencies(&[]);
     let contract = setup_contract(deps.as_mut());
-    let minter = mock_info(MINTER, &[]);
-
-    // Mint a couple tokens (from the same owner)
-    let token_id1 = TokenId::new(1);
-    let demeter = String::from("Demeter");
-    let token_id2 = TokenId::new(2);
-    let ceres = String::from("Ceres");
-    let token_id3 = TokenId::new(3);
 
+    let token_id = TokenId::new(1);
     let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
-        token_id: token_id1.clone(),
-        owner: demeter.clone(),
+        token_id: Some(token_id),
+        owner: String::from("venus"),
         token_uri: None,
         extension: Metadata::new_test(),
+        royalty: None,
+        transferable: None,
     }));
     contract
-        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+        .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), mint_msg)
         .unwrap();
+    let soon = Expiration::AtHeight(12350);
+    let venus = mock_info("venus", &[]);
+    let msgs = vec![
+        ExecuteMsg::Approve {
+            spender: String::from("random"),
+            token_id,
+            expires: Some(soon),
+        },
+        ExecuteMsg::ApproveAll {
+            operator: String::from("market"),
+            expires: None,
+        },
+    ];
+    for msg in msgs {
+        contract
+            .execute(deps.as_mut(), mock_env(), venus.clone(), msg)
+            .unwrap();
+    }
+    let mut late_env = mock_env();
+    late_env.block.height = 12351;
 
-    let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
-        token_id: token_id2.clone(),
-        owner: ceres.clone(),
-        token_uri: None,
-        extension: Metadata::new_test(),
-    }));
-    contract
-        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+    // single approvals, the owner is always approved
+    let res: ApprovalResponse = from_binary(
+        &contract
+            .query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Approval {
+                    token_id,
+                    spender: String::from("random"),
+                    include_expired: None,
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    let random_approval = crate::Approval {
+        spender: String::from("random"),
+        expires: soon,
+    };
+    assert_eq!(res.approval, random_approval);
+    let res = contract
+        .approval(
+            deps.as_ref(),
+            mock_env(),
+            token_id,
+            String::from("venus"),
+            false,
+        )
         .unwrap();
-
-    let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
-        token_id: token_id3.clone(),
-        owner: demeter.clone(),
-        token_uri: None,
-        extension: Metadata::new_test(),
-    }));
+    assert_eq!(res.approval.expires, Expiration::Never {});
     contract
-        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
+        .approval(
+            deps.as_ref(),
+            late_env.clone(),
+            token_id,
+            String::from("random"),
+            false,
+        )
+        .unwrap_err();
+    contract
+        .approval(
+            deps.as_ref(),
+            mock_env(),
+            token_id,
+            String::from("market"),
+            false,
+        )
+        .unwrap_err();
+
+    // all approvals of the token
+    let res = contract
+        .approvals(deps.as_ref(), late_env.clone(), token_id, true)
         .unwrap();
+    assert_eq!(res.approvals, vec![random_approval]);
+    let res = contract
+        .approvals(deps.as_ref(), late_env.clone(), token_id, false)
+        .unwrap();
+    assert!(res.approvals.is_empty());
 
-    // get all tokens in order:
-    let expected = vec![token_id1.clone(), token_id2.clone(), token_id3.clone()];
-    let tokens = contract.all_tokens(deps.as_ref(), None, None).unwrap();
-    assert_eq!(&expected, &tokens.tokens);
-    // paginate
-    let tokens = contract.all_tokens(deps.as_ref(), None, Some(2)).unwrap();
-    assert_eq!(&expected[..2], &tokens.tokens[..]);
-    let tokens = contract
-        .all_tokens(deps.as_ref(), Some(expected[1].clone()), None)
-        .unwrap();
-    assert_eq!(&expected[2..], &tokens.tokens[..]);
-
-    // get by owner
-    let by_ceres = vec![token_id2];
-    let by_demeter = vec![token_id1, token_id3];
-    // all tokens by owner
-    let tokens = contract
-        .tokens(deps.as_ref(), demeter.clone(), None, None)
+    // who can move the token, and why
+    let can_transfer = |env, sender: &str| {
+        let res: CanTransferResponse = from_binary(
+            &contract
+                .query(
+                    deps.as_ref(),
+                    env,
+                    QueryMsg::CanTransfer {
+                        token_id,
+                        sender: String::from(sender),
+                    },
+                )
+                .unwrap(),
+        )
         .unwrap();
-    assert_eq!(&by_demeter, &tokens.tokens);
-    let tokens = contract.tokens(deps.as_ref(), ceres, None, None).unwrap();
-    assert_eq!(&by_ceres, &tokens.tokens);
+        res.authority
+    };
+    assert_eq!(
+        can_transfer(mock_env(), "venus"),
+        Some(TransferAuthority::Owner)
+    );
+    assert_eq!(
+        can_transfer(mock_env(), "random"),
+        Some(TransferAuthority::TokenApproval)
+    );
+    assert_eq!(
+        can_transfer(mock_env(), "market"),
+        Some(TransferAuthority::Operator)
+    );
+    assert_eq!(can_transfer(mock_env(), "stranger"), None);
+    assert_eq!(can_transfer(late_env, "random"), None);
+    assert_eq!(
+        contract
+            .can_transfer(
+                deps.as_ref(),
+                mock_env(),
+                token_id,
+                String::from("stranger")
+            )
+            .unwrap(),
+        CanTransferResponse {
+            can_transfer: false,
+            authority: None,
+            blocked_by: None,
+        }
+    );
 
-    // paginate for demeter
-    let tokens = contract
-        .tokens(deps.as_ref(), demeter.clone(), None, Some(1))
+    // paused and soulbound tokens cannot move, whoever asks
+    let minter = mock_info(MINTER, &[]);
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::GrantRole {
+                role: Role::Pauser,
+                address: String::from(MINTER),
+            },
+        )
         .unwrap();
-    assert_eq!(&by_demeter[..1], &tokens.tokens[..]);
-    let tokens = contract
-        .tokens(deps.as_ref(), demeter, Some(by_demeter[0].clone()), Some(3))
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::Pause {
+                scope: PauseScope::Transfer,
+            },
+        )
         .unwrap();
-    assert_eq!(&by_demeter[1..], &tokens.tokens[..]);
-}
-
-#[test]
-fn use_metadata_extension() {
-    const CREATOR: &str = "creator";
-
-    let mut deps = mock_dependencies(&[]);
-    let contract = DummyNftContract::default();
-
-    let info = mock_info(CREATOR, &[]);
-    let init_msg = InstantiateMsg {
-        name: "SpaceShips".to_string(),
-        symbol: "SPACE".to_string(),
-        minter: CREATOR.to_string(),
-    };
+    assert_eq!(
+        contract
+            .can_transfer(deps.as_ref(), mock_env(), token_id, String::from("venus"))
+            .unwrap(),
+        CanTransferResponse {
+            can_transfer: false,
+            authority: Some(TransferAuthority::Owner),
+            blocked_by: Some(TransferBlock::Paused),
+        }
+    );
     contract
-        .instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg)
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::Unpause {},
+        )
         .unwrap();
 
-    let token_id = TokenId::new(1701);
-    let mint_msg = MintMsg {
-        token_id,
-        owner: "john".to_string(),
-        token_uri: Some("https://starships.example.com/Starship/Enterprise.json".into()),
+    let soulbound_id = TokenId::new(2);
+    let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
+        token_id: Some(soulbound_id),
+        owner: String::from("venus"),
+        token_uri: None,
         extension: Metadata::new_test(),
-    };
-    let exec_msg = ExecuteMsg::Mint(Box::new(mint_msg.clone()));
+        royalty: None,
+        transferable: Some(false),
+    }));
     contract
-        .execute(deps.as_mut(), mock_env(), info, exec_msg)
+        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
         .unwrap();
-
-    let res = contract.nft_info(deps.as_ref(), token_id.into()).unwrap();
-    assert_eq!(res.token_uri, mint_msg.token_uri);
-    assert_eq!(res.extension, mint_msg.extension);
+    assert_eq!(
+        contract
+            .can_transfer(
+                deps.as_ref(),
+                mock_env(),
+                soulbound_id,
+                String::from("venus")
+            )
+            .unwrap(),
+        CanTransferResponse {
+            can_transfer: false,
+            authority: Some(TransferAuthority::Owner),
+            blocked_by: Some(TransferBlock::NonTransferable),
+        }
+    );
 }
 
 #[test]
-fn burn_and_reuse() {
+fn approval_limit() {
     let mut deps = mock_dependencies(&[]);
     let contract = setup_contract(deps.as_mut());
+    assert_eq!(
+        contract.approval_limit(deps.as_ref()).unwrap(),
+        ApprovalLimitResponse { max_approvals: 10 }
+    );
 
     let token_id = TokenId::new(1);
-    let token_uri = "https://www.merriam-webster.com/dictionary/petrify".to_string();
-
     let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
-        token_id,
-        owner: MINTER.to_string(),
-        token_uri: Some(token_uri),
+        token_id: Some(token_id),
+        owner: String::from("venus"),
+        token_uri: None,
         extension: Metadata::new_test(),
+        royalty: None,
+        transferable: None,
     }));
-
-    let burn_msg = ExecuteMsg::Burn { token_id };
-
-    // mint some NFT
-    let allowed = mock_info(MINTER, &[]);
-    let _ = contract
-        .execute(deps.as_mut(), mock_env(), allowed.clone(), mint_msg.clone())
+    contract
+        .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), mint_msg)
         .unwrap();
+    let approve_msg = |spender: &str| ExecuteMsg::Approve {
+        spender: String::from(spender),
+        token_id,
+        expires: None,
+    };
+    let venus = mock_info("venus", &[]);
+    for spender in ["mars", "jupiter", "saturn"] {
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                venus.clone(),
+                approve_msg(spender),
+            )
+            .unwrap();
+    }
 
-    // Burn it
-    let _ = contract
-        .execute(deps.as_mut(), mock_env(), allowed.clone(), burn_msg)
+    // lower the limit below what the token already holds
+    contract
+        .migrate(
+            deps.as_mut(),
+            MigrateMsg {
+                max_approvals: Some(2),
+                ..Default::default()
+            },
+        )
         .unwrap();
+    let res: ApprovalLimitResponse = from_binary(
+        &contract
+            .query(deps.as_ref(), mock_env(), QueryMsg::ApprovalLimit {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res, ApprovalLimitResponse { max_approvals: 2 });
 
-    // Cannot remint
+    // no new approvals can be added
     let err = contract
-        .execute(deps.as_mut(), mock_env(), allowed, mint_msg)
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            venus.clone(),
+            approve_msg("pluto"),
+        )
         .unwrap_err();
+    assert_eq!(err, ContractError::TooManyApprovals { max: 2 });
 
-    assert_eq!(err, ContractError::RemintBurned { token_id });
+    // but the token can still be moved, which clears its approvals
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("saturn", &[]),
+            ExecuteMsg::TransferNft {
+                recipient: String::from("saturn"),
+                token_id,
+            },
+        )
+        .unwrap();
+    let saturn = mock_info("saturn", &[]);
+    for spender in ["mars", "jupiter"] {
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                saturn.clone(),
+                approve_msg(spender),
+            )
+            .unwrap();
+    }
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), saturn, approve_msg("pluto"))
+        .unwrap_err();
+    assert_eq!(err, ContractError::TooManyApprovals { max: 2 });
 }
 
 #[test]
-fn highest_token_id() {
+fn pruning_expired() {
     let mut deps = mock_dependencies(&[]);
     let contract = setup_contract(deps.as_mut());
 
-    // Starts as None
-    assert_eq!(
-        contract.highest_token_id(deps.as_ref()).unwrap(),
-        HighestTokenIdResponse {
-            highest_token_id: None
-        }
-    );
+    for token_id in 1..=2 {
+        let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
+            token_id: Some(TokenId::new(token_id)),
+            owner: String::from("venus"),
+            token_uri: None,
+            extension: Metadata::new_test(),
+            royalty: None,
+            transferable: None,
+        }));
+        contract
+            .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), mint_msg)
+            .unwrap();
+    }
 
-    let token_id = TokenId::new(1);
-    let token_uri = "https://www.merriam-webster.com/dictionary/petrify".to_string();
-    let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
-        token_id,
-        owner: MINTER.to_string(),
-        token_uri: Some(token_uri),
-        extension: Metadata::new_test(),
-    }));
-    let allowed = mock_info(MINTER, &[]);
-    let _ = contract
-        .execute(deps.as_mut(), mock_env(), allowed.clone(), mint_msg.clone())
-        .unwrap();
+    // mock_env is at height 12345, so these expire soon
+    let soon = Some(Expiration::AtHeight(12350));
+    let venus = mock_info("venus", &[]);
+    let msgs = vec![
+        ExecuteMsg::Approve {
+            spender: String::from("random"),
+            token_id: TokenId::new(1),
+            expires: soon,
+        },
+        ExecuteMsg::Approve {
+            spender: String::from("buddy"),
+            token_id: TokenId::new(1),
+            expires: None,
+        },
+        ExecuteMsg::Approve {
+            spender: String::from("random"),
+            token_id: TokenId::new(2),
+            expires: soon,
+        },
+        ExecuteMsg::ApproveAll {
+            operator: String::from("market"),
+            expires: soon,
+        },
+        ExecuteMsg::ApproveAll {
+            operator: String::from("buddy"),
+            expires: None,
+        },
+    ];
+    for msg in msgs {
+        contract
+            .execute(deps.as_mut(), mock_env(), venus.clone(), msg)
+            .unwrap();
+    }
 
-    // ID has been updated
-    assert_eq!(
-        contract.highest_token_id(deps.as_ref()).unwrap(),
-        HighestTokenIdResponse {
-            highest_token_id: Some(token_id)
-        }
-    );
+    let mut late_env = mock_env();
+    late_env.block.height = 12351;
 
-    // Burning does not affect it
-    let burn_msg = ExecuteMsg::Burn { token_id };
-    let _ = contract
-        .execute(deps.as_mut(), mock_env(), allowed.clone(), burn_msg)
+    // anyone can prune, the limit bounds how much is removed
+    let random = mock_info("random", &[]);
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            late_env.clone(),
+            random.clone(),
+            ExecuteMsg::PruneExpired {
+                token_ids: vec![TokenId::new(1), TokenId::new(2)],
+                owners: vec![String::from("venus")],
+                limit: Some(1),
+            },
+        )
         .unwrap();
     assert_eq!(
-        contract.highest_token_id(deps.as_ref()).unwrap(),
-        HighestTokenIdResponse {
-            highest_token_id: Some(token_id),
-        }
+        res,
+        Response::new()
+            .add_attribute("action", "prune_expired")
+            .add_attribute("sender", "random")
+            .add_attribute("approvals_removed", "1")
+            .add_attribute("operators_removed", "0")
     );
+    let approvals = |deps: Deps, token_id: u64| {
+        contract
+            .owner_of(deps, mock_env(), TokenId::new(token_id), true)
+            .unwrap()
+            .approvals
+            .into_iter()
+            .map(|apr| apr.spender)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(approvals(deps.as_ref(), 1), vec!["buddy"]);
+    assert_eq!(approvals(deps.as_ref(), 2), vec!["random"]);
 
-    // Minting again updates
-    let token_id = TokenId::new(5);
-    let token_uri = "https://www.merriam-webster.com/dictionary/petrify".to_string();
-    let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
-        token_id,
-        owner: MINTER.to_string(),
-        token_uri: Some(token_uri),
-        extension: Metadata::new_test(),
-    }));
-    let allowed = mock_info(MINTER, &[]);
-    let _ = contract
-        .execute(deps.as_mut(), mock_env(), allowed.clone(), mint_msg.clone())
+    // burned tokens are skipped
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            venus.clone(),
+            ExecuteMsg::Burn {
+                token_id: TokenId::new(1),
+            },
+        )
+        .unwrap();
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            late_env.clone(),
+            random,
+            ExecuteMsg::PruneExpired {
+                token_ids: vec![TokenId::new(1), TokenId::new(2)],
+                owners: vec![String::from("venus")],
+                limit: None,
+            },
+        )
         .unwrap();
     assert_eq!(
-        contract.highest_token_id(deps.as_ref()).unwrap(),
-        HighestTokenIdResponse {
-            highest_token_id: Some(token_id),
-        }
+        res,
+        Response::new()
+            .add_attribute("action", "prune_expired")
+            .add_attribute("sender", "random")
+            .add_attribute("approvals_removed", "1")
+            .add_attribute("operators_removed", "1")
     );
-
-    // Minting lower has no affect updates
-    let lower_token_id = TokenId::new(4);
-    let token_uri = "https://www.merriam-webster.com/dictionary/petrify".to_string();
-    let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
-        token_id: lower_token_id,
-        owner: MINTER.to_string(),
-        token_uri: Some(token_uri),
-        extension: Metadata::new_test(),
-    }));
-    let allowed = mock_info(MINTER, &[]);
-    let _ = contract
-        .execute(deps.as_mut(), mock_env(), allowed.clone(), mint_msg.clone())
+    assert!(approvals(deps.as_ref(), 2).is_empty());
+    let operators = contract
+        .all_approvals(
+            deps.as_ref(),
+            mock_env(),
+            String::from("venus"),
+            true,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
         .unwrap();
     assert_eq!(
-        contract.highest_token_id(deps.as_ref()).unwrap(),
-        HighestTokenIdResponse {
-            highest_token_id: Some(token_id),
-        }
+        operators.operators,
+        vec![crate::Approval {
+            spender: String::from("buddy"),
+            expires: Expiration::Never {},
+        }]
     );
+    let grants = contract
+        .operator_grants(
+            deps.as_ref(),
+            mock_env(),
+            String::from("market"),
+            true,
+            None,
+            None,
+        )
+        .unwrap();
+    assert!(grants.grants.is_empty());
+
+    // approving again drops expired approvals on the way
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            venus.clone(),
+            ExecuteMsg::Approve {
+                spender: String::from("random"),
+                token_id: TokenId::new(2),
+                expires: soon,
+            },
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            late_env,
+            venus,
+            ExecuteMsg::Approve {
+                spender: String::from("buddy"),
+                token_id: TokenId::new(2),
+                expires: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(approvals(deps.as_ref(), 2), vec!["buddy"]);
 }
 
+#[test]
+fn querying_operator_grants() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+
+    let market_expires = Expiration::AtHeight(1234567);
+    for (owner, expires) in [
+        ("venus", None),
+        ("mars", Some(market_expires)),
+        ("jupiter", None),
+    ] {
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(owner, &[]),
+                ExecuteMsg::ApproveAll {
+                    operator: String::from("market"),
+                    expires,
+                },
+            )
+            .unwrap();
+    }
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[]),
+            ExecuteMsg::ApproveAll {
+                operator: String::from("buddy"),
+                expires: None,
+            },
+        )
+        .unwrap();
+
+    // the operator sees every owner that approved it, paginated by owner
+    let res = contract
+        .operator_grants(
+            deps.as_ref(),
+            mock_env(),
+            String::from("market"),
+            false,
+            None,
+            Some(2),
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        OperatorGrantsResponse {
+            grants: vec![
+                OperatorGrant {
+                    owner: String::from("jupiter"),
+                    expires: Expiration::Never {},
+                },
+                OperatorGrant {
+                    owner: String::from("mars"),
+                    expires: market_expires,
+                },
+            ]
+        }
+    );
+    let res = contract
+        .operator_grants(
+            deps.as_ref(),
+            mock_env(),
+            String::from("market"),
+            false,
+            Some(String::from("mars")),
+            None,
+        )
+        .unwrap();
+    assert_eq!(res.grants.len(), 1);
+    assert_eq!(res.grants[0].owner, "venus");
+
+    // revoking removes the grant, expired grants are filtered
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("jupiter", &[]),
+            ExecuteMsg::RevokeAll {
+                operator: String::from("market"),
+            },
+        )
+        .unwrap();
+    let mut late_env = mock_env();
+    late_env.block.height = 1234568;
+    let owners = |deps: Deps, env, include_expired| {
+        contract
+            .operator_grants(
+                deps,
+                env,
+                String::from("market"),
+                include_expired,
+                None,
+                None,
+            )
+            .unwrap()
+            .grants
+            .into_iter()
+            .map(|grant| grant.owner)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        owners(deps.as_ref(), late_env.clone(), false),
+        vec!["venus"]
+    );
+    assert_eq!(
+        owners(deps.as_ref(), late_env.clone(), true),
+        vec!["mars", "venus"]
+    );
+
+    // migrating rebuilds the index for grants made before it existed, but only once
+    let migrate_msg = MigrateMsg::default();
+    contract.operator_grants.remove(
+        &mut deps.storage,
+        (&Addr::unchecked("market"), &Addr::unchecked("venus")),
+    );
+    contract
+        .migrate(deps.as_mut(), migrate_msg.clone())
+        .unwrap();
+    assert_eq!(owners(deps.as_ref(), late_env.clone(), true), vec!["mars"]);
+    contract.operator_index_version.remove(&mut deps.storage);
+    contract.migrate(deps.as_mut(), migrate_msg).unwrap();
+    assert_eq!(owners(deps.as_ref(), late_env, true), vec!["mars", "venus"]);
+}
+
+#[test]
+fn query_tokens_by_owner() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER, &[]);
+
+    // Mint a couple tokens (from the same owner)
+    let token_id1 = TokenId::new(1);
+    let demeter = String::from("Demeter");
+    let token_id2 = TokenId::new(2);
+    let ceres = String::from("Ceres");
+    let token_id3 = TokenId::new(3);
+
+    let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
+        token_id: Some(token_id1),
+        owner: demeter.clone(),
+        token_uri: None,
+        extension: Metadata::new_test(),
+        royalty: None,
+        transferable: None,
+    }));
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+        .unwrap();
+
+    let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
+        token_id: Some(token_id2),
+        owner: ceres.clone(),
+        token_uri: None,
+        extension: Metadata::new_test(),
+        royalty: None,
+        transferable: None,
+    }));
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+        .unwrap();
+
+    let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
+        token_id: Some(token_id3),
+        owner: demeter.clone(),
+        token_uri: None,
+        extension: Metadata::new_test(),
+        royalty: None,
+        transferable: None,
+    }));
+    contract
+        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
+        .unwrap();
+
+    // get all tokens in order:
+    let expected = vec![token_id1, token_id2, token_id3];
+    let tokens = contract
+        .all_tokens(deps.as_ref(), None, None, None, None, None)
+        .unwrap();
+    assert_eq!(&expected, &tokens.tokens);
+    // paginate
+    let tokens = contract
+        .all_tokens(deps.as_ref(), None, Some(2), None, None, None)
+        .unwrap();
+    assert_eq!(&expected[..2], &tokens.tokens[..]);
+    let tokens = contract
+        .all_tokens(deps.as_ref(), Some(expected[1]), None, None, None, None)
+        .unwrap();
+    assert_eq!(&expected[2..], &tokens.tokens[..]);
+
+    // get by owner
+    let by_ceres = vec![token_id2];
+    let by_demeter = vec![token_id1, token_id3];
+    // all tokens by owner
+    let tokens = contract
+        .tokens(deps.as_ref(), demeter.clone(), None, None, None, None, None)
+        .unwrap();
+    assert_eq!(&by_demeter, &tokens.tokens);
+    let tokens = contract
+        .tokens(deps.as_ref(), ceres, None, None, None, None, None)
+        .unwrap();
+    assert_eq!(&by_ceres, &tokens.tokens);
+
+    // paginate for demeter
+    let tokens = contract
+        .tokens(
+            deps.as_ref(),
+            demeter.clone(),
+            None,
+            Some(1),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    assert_eq!(&by_demeter[..1], &tokens.tokens[..]);
+    let tokens = contract
+        .tokens(
+            deps.as_ref(),
+            demeter,
+            Some(by_demeter[0]),
+            Some(3),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    assert_eq!(&by_demeter[1..], &tokens.tokens[..]);
+}
+
+/// Checks the balance counters against the owner index for every owner
+fn assert_balances_match_index(deps: Deps, contract: &DummyNftContract, owners: &[&str]) {
+    for owner in owners {
+        let indexed = contract
+            .tokens
+            .idx
+            .owner
+            .prefix(Addr::unchecked(*owner))
+            .keys(deps.storage, None, None, Order::Ascending)
+            .count() as u64;
+        let res = contract.balance_of(deps, String::from(*owner)).unwrap();
+        assert_eq!(res.balance, indexed, "balance of {}", owner);
+    }
+}
+
+#[test]
+fn owner_balances() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+    let owners = ["venus", "mars", "jupiter", "another_contract"];
+
+    let mint = |owner: &str| MintMsg {
+        token_id: None,
+        owner: String::from(owner),
+        token_uri: None,
+        extension: Metadata::new_test(),
+        royalty: None,
+        transferable: None,
+    };
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::BatchMint {
+                mints: vec![
+                    mint("venus"),
+                    mint("venus"),
+                    mint("venus"),
+                    mint("mars"),
+                    mint("mars"),
+                    mint("jupiter"),
+                ],
+            },
+        )
+        .unwrap();
+    assert_balances_match_index(deps.as_ref(), &contract, &owners);
+
+    // transfers, burns and returned sends all move the counters
+    let venus = mock_info("venus", &[]);
+    let msgs = vec![
+        ExecuteMsg::TransferNft {
+            recipient: String::from("jupiter"),
+            token_id: TokenId::new(1),
+        },
+        ExecuteMsg::BatchTransferNft {
+            recipient: String::from("mars"),
+            token_ids: vec![TokenId::new(2)],
+        },
+        ExecuteMsg::SendNft {
+            contract: String::from("another_contract"),
+            token_id: TokenId::new(3),
+            msg: to_binary("hello").unwrap(),
+            return_on_failure: Some(true),
+        },
+    ];
+    for msg in msgs {
+        contract
+            .execute(deps.as_mut(), mock_env(), venus.clone(), msg)
+            .unwrap();
+    }
+    assert_balances_match_index(deps.as_ref(), &contract, &owners);
+    contract
+        .reply(
+            deps.as_mut(),
+            mock_env(),
+            Reply {
+                id: 3,
+                result: ContractResult::Err(String::from("no thanks")),
+            },
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("jupiter", &[]),
+            ExecuteMsg::Burn {
+                token_id: TokenId::new(6),
+            },
+        )
+        .unwrap();
+    assert_balances_match_index(deps.as_ref(), &contract, &owners);
+
+    // mars holds 3, venus and jupiter 1 each
+    let res: BalanceResponse = from_binary(
+        &contract
+            .query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Balance {
+                    owner: String::from("mars"),
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res, BalanceResponse { balance: 3 });
+    let holder = |owner: &str, balance| Holder {
+        owner: String::from(owner),
+        balance,
+    };
+    let res = contract.top_holders(deps.as_ref(), None, None).unwrap();
+    assert_eq!(
+        res.holders,
+        vec![holder("mars", 3), holder("venus", 1), holder("jupiter", 1)]
+    );
+    let res = contract
+        .top_holders(deps.as_ref(), Some((1, String::from("venus"))), Some(1))
+        .unwrap();
+    assert_eq!(res.holders, vec![holder("jupiter", 1)]);
+
+    // migrating rebuilds the counters of older versions once, without counting twice
+    contract
+        .migrate(deps.as_mut(), MigrateMsg::default())
+        .unwrap();
+    contract.balance_index_version.remove(&mut deps.storage);
+    contract
+        .migrate(deps.as_mut(), MigrateMsg::default())
+        .unwrap();
+    assert_balances_match_index(deps.as_ref(), &contract, &owners);
+    contract.balance_index_version.remove(&mut deps.storage);
+    for owner in owners {
+        let owner = Addr::unchecked(owner);
+        if let Some(balance) = contract.balances.may_load(&deps.storage, &owner).unwrap() {
+            contract.balances.remove(&mut deps.storage, &owner);
+            contract
+                .holders
+                .remove(&mut deps.storage, (U64Key::new(balance), &owner));
+        }
+    }
+    contract
+        .migrate(deps.as_mut(), MigrateMsg::default())
+        .unwrap();
+    assert_balances_match_index(deps.as_ref(), &contract, &owners);
+    let res = contract.top_holders(deps.as_ref(), None, Some(2)).unwrap();
+    assert_eq!(res.holders, vec![holder("mars", 3), holder("venus", 1)]);
+
+    // the cursor still works after its holder was emptied between pages
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            venus,
+            ExecuteMsg::TransferNft {
+                recipient: String::from("mars"),
+                token_id: TokenId::new(3),
+            },
+        )
+        .unwrap();
+    let res = contract
+        .top_holders(deps.as_ref(), Some((1, String::from("venus"))), Some(2))
+        .unwrap();
+    assert_eq!(res.holders, vec![holder("jupiter", 1)]);
+}
+
+#[test]
+fn query_ranges_and_order() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+
+    let minter = mock_info(MINTER, &[]);
+    for token_id in 1..=5 {
+        let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
+            token_id: Some(TokenId::new(token_id)),
+            owner: String::from(if token_id == 5 { "mars" } else { "venus" }),
+            token_uri: None,
+            extension: Metadata::new_test(),
+            royalty: None,
+            transferable: None,
+        }));
+        contract
+            .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+            .unwrap();
+    }
+    let ids = |ids: &[u64]| ids.iter().map(|&id| TokenId::new(id)).collect::<Vec<_>>();
+    let all_tokens = |start_after: Option<u64>, end: Option<u64>, inclusive, order| {
+        contract
+            .all_tokens(
+                deps.as_ref(),
+                start_after.map(TokenId::new),
+                None,
+                end.map(TokenId::new),
+                inclusive,
+                order,
+            )
+            .unwrap()
+            .tokens
+    };
+
+    // newest first
+    let desc = Some(OrderBy::Descending);
+    assert_eq!(all_tokens(None, None, None, desc), ids(&[5, 4, 3, 2, 1]));
+    assert_eq!(all_tokens(Some(4), None, None, desc), ids(&[3, 2, 1]));
+    assert_eq!(
+        all_tokens(Some(4), Some(2), Some(true), desc),
+        ids(&[4, 3, 2])
+    );
+
+    // both ends bound the range, exclusive unless asked otherwise
+    assert_eq!(all_tokens(Some(1), Some(4), None, None), ids(&[2, 3]));
+    assert_eq!(
+        all_tokens(Some(1), Some(4), Some(true), Some(OrderBy::Ascending)),
+        ids(&[1, 2, 3, 4])
+    );
+
+    // by owner through the query message
+    let res: TokensResponse = from_binary(
+        &contract
+            .query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Tokens {
+                    owner: String::from("venus"),
+                    start_after: None,
+                    limit: Some(2),
+                    end: Some(TokenId::new(1)),
+                    inclusive: None,
+                    order: desc,
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.tokens, ids(&[4, 3]));
+
+    // operators
+    let venus = mock_info("venus", &[]);
+    for operator in ["buddy", "market", "operator"] {
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                venus.clone(),
+                ExecuteMsg::ApproveAll {
+                    operator: String::from(operator),
+                    expires: None,
+                },
+            )
+            .unwrap();
+    }
+    let res = contract
+        .all_approvals(
+            deps.as_ref(),
+            mock_env(),
+            String::from("venus"),
+            false,
+            Some(String::from("operator")),
+            None,
+            None,
+            Some(true),
+            desc,
+        )
+        .unwrap();
+    let operators: Vec<_> = res.operators.into_iter().map(|op| op.spender).collect();
+    assert_eq!(operators, vec!["operator", "market", "buddy"]);
+
+    // ordering is numeric, also across byte boundaries
+    let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
+        token_id: Some(TokenId::new(256)),
+        owner: String::from("mars"),
+        token_uri: None,
+        extension: Metadata::new_test(),
+        royalty: None,
+        transferable: None,
+    }));
+    contract
+        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
+        .unwrap();
+    let res = contract
+        .all_tokens(deps.as_ref(), None, Some(3), None, None, desc)
+        .unwrap();
+    assert_eq!(res.tokens, ids(&[256, 5, 4]));
+}
+
+#[test]
+fn migrating_token_keys() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+
+    // write state the way older versions did: little-endian with trailing zeroes removed
+    contract.token_key_version.remove(&mut deps.storage);
+    let legacy_tokens: IndexedMap<Vec<u8>, TokenInfo, TokenIndexes> = IndexedMap::new(
+        "tokens",
+        TokenIndexes {
+            owner: MultiIndex::new(token_owner_idx, "tokens", "tokens__owner"),
+        },
+    );
+    let token = TokenInfo {
+        owner: Addr::unchecked("venus"),
+        approvals: vec![],
+        token_uri: None,
+        extension: Metadata::new_test(),
+    };
+    for key in [vec![2], vec![0, 1], vec![3]] {
+        legacy_tokens.save(&mut deps.storage, key, &token).unwrap();
+    }
+    Map::<Vec<u8>, ()>::new("burned")
+        .save(&mut deps.storage, vec![5], &())
+        .unwrap();
+
+    contract
+        .migrate(deps.as_mut(), MigrateMsg::default())
+        .unwrap();
+
+    // tokens now come back in numeric order, by owner as well
+    let ids = |ids: &[u64]| ids.iter().map(|&id| TokenId::new(id)).collect::<Vec<_>>();
+    let tokens = contract
+        .all_tokens(deps.as_ref(), None, None, None, None, None)
+        .unwrap();
+    assert_eq!(tokens.tokens, ids(&[2, 3, 256]));
+    let tokens = contract
+        .all_tokens(deps.as_ref(), Some(TokenId::new(2)), None, None, None, None)
+        .unwrap();
+    assert_eq!(tokens.tokens, ids(&[3, 256]));
+    let tokens = contract
+        .tokens(
+            deps.as_ref(),
+            String::from("venus"),
+            None,
+            None,
+            None,
+            None,
+            Some(OrderBy::Descending),
+        )
+        .unwrap();
+    assert_eq!(tokens.tokens, ids(&[256, 3, 2]));
+
+    // burned tokens moved along
+    let owner = contract
+        .owner_of(deps.as_ref(), mock_env(), TokenId::new(256), false)
+        .unwrap();
+    assert_eq!(owner.owner, "venus");
+    assert!(contract.burned.has(&deps.storage, TokenId::new(5)));
+    assert!(!Map::<Vec<u8>, ()>::new("burned").has(&deps.storage, vec![5]));
+
+    // migrating again leaves the new keys alone
+    contract
+        .migrate(deps.as_mut(), MigrateMsg::default())
+        .unwrap();
+    let tokens = contract
+        .all_tokens(deps.as_ref(), None, None, None, None, None)
+        .unwrap();
+    assert_eq!(tokens.tokens, ids(&[2, 3, 256]));
+}
+
+#[test]
+fn querying_by_trait() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+
+    let with_traits = |traits: &[(&str, &str)]| {
+        let mut extension = Metadata::new_test();
+        extension.attributes = traits
+            .iter()
+            .map(|(trait_type, value)| Trait {
+                display_type: None,
+                trait_type: trait_type.to_string(),
+                value: value.to_string(),
+            })
+            .collect();
+        extension
+    };
+    let minter = mock_info(MINTER, &[]);
+    let tokens = vec![
+        with_traits(&[("background", "gold"), ("eyes", "blue")]),
+        with_traits(&[("background", "gold"), ("background", "gold")]),
+        with_traits(&[("background", "silver")]),
+    ];
+    for (i, extension) in tokens.into_iter().enumerate() {
+        let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
+            token_id: Some(TokenId::new(i as u64 + 1)),
+            owner: String::from("venus"),
+            token_uri: None,
+            extension,
+            royalty: None,
+            transferable: None,
+        }));
+        contract
+            .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+            .unwrap();
+    }
+
+    let by_trait = |deps: Deps, value: &str| {
+        contract
+            .tokens_by_trait(
+                deps,
+                String::from("background"),
+                String::from(value),
+                None,
+                None,
+            )
+            .unwrap()
+            .tokens
+    };
+    let values = |deps: Deps| {
+        contract
+            .trait_values(deps, String::from("background"), None, None)
+            .unwrap()
+            .values
+            .into_iter()
+            .map(|v| (v.value, v.count))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        by_trait(deps.as_ref(), "gold"),
+        vec![TokenId::new(1), TokenId::new(2)]
+    );
+    // a trait listed twice on one token counts once
+    assert_eq!(
+        values(deps.as_ref()),
+        vec![("gold".to_string(), 2), ("silver".to_string(), 1)]
+    );
+
+    // paginate through the query message
+    let res: TokensResponse = from_binary(
+        &contract
+            .query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::TokensByTrait {
+                    trait_type: String::from("background"),
+                    value: String::from("gold"),
+                    start_after: Some(TokenId::new(1)),
+                    limit: None,
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.tokens, vec![TokenId::new(2)]);
+    let res: TraitValuesResponse = from_binary(
+        &contract
+            .query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::TraitValues {
+                    trait_type: String::from("eyes"),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.values,
+        vec![TraitValue {
+            value: String::from("blue"),
+            count: 1,
+        }]
+    );
+
+    // metadata updates move the token between values
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter,
+            ExecuteMsg::UpdateMetadata {
+                token_id: TokenId::new(2),
+                token_uri: None,
+                extension: Some(with_traits(&[("background", "silver")])),
+            },
+        )
+        .unwrap();
+    assert_eq!(by_trait(deps.as_ref(), "gold"), vec![TokenId::new(1)]);
+    assert_eq!(
+        by_trait(deps.as_ref(), "silver"),
+        vec![TokenId::new(2), TokenId::new(3)]
+    );
+
+    // burned tokens leave the index, values without tokens disappear
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[]),
+            ExecuteMsg::Burn {
+                token_id: TokenId::new(1),
+            },
+        )
+        .unwrap();
+    assert!(by_trait(deps.as_ref(), "gold").is_empty());
+    assert_eq!(values(deps.as_ref()), vec![("silver".to_string(), 2)]);
+    let res = contract
+        .trait_values(deps.as_ref(), String::from("eyes"), None, None)
+        .unwrap();
+    assert!(res.values.is_empty());
+
+    // migrating indexes the tokens of older versions, but only once
+    let migrate_msg = MigrateMsg::default();
+    let token = contract
+        .tokens
+        .load(&deps.storage, TokenId::new(2))
+        .unwrap();
+    contract
+        .unindex_traits(&mut deps.storage, TokenId::new(2), &token.extension)
+        .unwrap();
+    contract
+        .migrate(deps.as_mut(), migrate_msg.clone())
+        .unwrap();
+    assert_eq!(by_trait(deps.as_ref(), "silver"), vec![TokenId::new(3)]);
+    contract.trait_index_version.remove(&mut deps.storage);
+    contract.migrate(deps.as_mut(), migrate_msg).unwrap();
+    assert_eq!(
+        by_trait(deps.as_ref(), "silver"),
+        vec![TokenId::new(2), TokenId::new(3)]
+    );
+}
+
+#[test]
+fn trait_rarity() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+
+    let with_traits = |traits: &[(&str, &str)]| {
+        let mut extension = Metadata::new_test();
+        extension.attributes = traits
+            .iter()
+            .map(|(trait_type, value)| Trait {
+                display_type: None,
+                trait_type: trait_type.to_string(),
+                value: value.to_string(),
+            })
+            .collect();
+        extension
+    };
+    let tokens = vec![
+        with_traits(&[("background", "gold"), ("eyes", "blue")]),
+        with_traits(&[("background", "silver")]),
+        with_traits(&[("background", "silver")]),
+        with_traits(&[("background", "silver")]),
+    ];
+    let minter = mock_info(MINTER, &[]);
+    for (i, extension) in tokens.into_iter().enumerate() {
+        let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
+            token_id: Some(TokenId::new(i as u64 + 1)),
+            owner: String::from("venus"),
+            token_uri: None,
+            extension,
+            royalty: None,
+            transferable: None,
+        }));
+        contract
+            .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+            .unwrap();
+    }
+
+    let stat = |value: &str, count, frequency| TraitStat {
+        trait_type: String::from("background"),
+        value: String::from(value),
+        count,
+        frequency,
+    };
+    let res: TraitStatsResponse = from_binary(
+        &contract
+            .query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::TraitStats {
+                    trait_type: String::from("background"),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        TraitStatsResponse {
+            total_tokens: 4,
+            values: vec![
+                stat("gold", 1, Decimal::percent(25)),
+                stat("silver", 3, Decimal::percent(75))
+            ],
+        }
+    );
+
+    // rarer tokens score higher
+    let res: TokenRarityResponse = from_binary(
+        &contract
+            .query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::TokenRarity {
+                    token_id: TokenId::new(1),
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.traits.len(), 2);
+    assert_eq!(res.traits[0], stat("gold", 1, Decimal::percent(25)));
+    assert_eq!(res.rarity_score, Decimal::percent(800));
+    let res = contract
+        .token_rarity(deps.as_ref(), TokenId::new(2))
+        .unwrap();
+    assert_eq!(res.traits, vec![stat("silver", 3, Decimal::percent(75))]);
+    assert_eq!(res.rarity_score, Decimal::from_ratio(4u64, 3u64));
+
+    // burns are reflected immediately
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[]),
+            ExecuteMsg::Burn {
+                token_id: TokenId::new(4),
+            },
+        )
+        .unwrap();
+    let res = contract
+        .trait_stats(deps.as_ref(), String::from("background"), None, None)
+        .unwrap();
+    assert_eq!(res.total_tokens, 3);
+    assert_eq!(res.values[1].count, 2);
+    assert_eq!(res.values[1].frequency, Decimal::from_ratio(2u64, 3u64));
+    let res = contract
+        .token_rarity(deps.as_ref(), TokenId::new(1))
+        .unwrap();
+    assert_eq!(res.rarity_score, Decimal::percent(600));
+}
+
+#[test]
+fn trait_schema_validation() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = DummyNftContract::default();
+
+    let rule = |trait_type: &str, required: bool, values: ValueRule| TraitRule {
+        trait_type: trait_type.to_string(),
+        required,
+        values,
+        display_types: None,
+    };
+    let schema = TraitSchema {
+        traits: vec![
+            rule(
+                "background",
+                true,
+                ValueRule::OneOf {
+                    values: vec![String::from("gold"), String::from("silver")],
+                },
+            ),
+            TraitRule {
+                display_types: Some(vec![String::from("boost_number")]),
+                ..rule("speed", false, ValueRule::Number {})
+            },
+            rule("born", false, ValueRule::Date {}),
+        ],
+    };
+    let instantiate_msg = |schema: TraitSchema| InstantiateMsg {
+        name: CONTRACT_NAME.to_string(),
+        symbol: SYMBOL.to_string(),
+        minter: String::from(MINTER),
+        trait_schema: Some(schema),
+        ..Default::default()
+    };
+
+    // duplicate rules are rejected
+    let mut duplicated = schema.clone();
+    duplicated
+        .traits
+        .push(rule("born", true, ValueRule::Any {}));
+    contract
+        .instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(duplicated),
+        )
+        .unwrap_err();
+    contract
+        .instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(schema.clone()),
+        )
+        .unwrap();
+    let res = contract.trait_schema(deps.as_ref()).unwrap();
+    assert_eq!(res.schema, Some(schema));
+
+    let attr = |trait_type: &str, value: &str, display_type: Option<&str>| Trait {
+        display_type: display_type.map(String::from),
+        trait_type: trait_type.to_string(),
+        value: value.to_string(),
+    };
+    let mint = |deps: DepsMut, token_id: u64, attributes: Vec<Trait>| {
+        let mut extension = Metadata::new_test();
+        extension.attributes = attributes;
+        let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
+            token_id: Some(TokenId::new(token_id)),
+            owner: String::from("venus"),
+            token_uri: None,
+            extension,
+            royalty: None,
+            transferable: None,
+        }));
+        contract.execute(deps, mock_env(), mock_info(MINTER, &[]), mint_msg)
+    };
+    let invalid = |trait_type: &str, reason: &str| ContractError::InvalidTrait {
+        trait_type: trait_type.to_string(),
+        reason: reason.to_string(),
+    };
+
+    let err = mint(deps.as_mut(), 1, vec![]).unwrap_err();
+    assert_eq!(err, invalid("background", "required trait is missing"));
+    let err = mint(
+        deps.as_mut(),
+        1,
+        vec![attr("background", "gold", None), attr("eyes", "blue", None)],
+    )
+    .unwrap_err();
+    assert_eq!(err, invalid("eyes", "unknown trait type"));
+    let err = mint(deps.as_mut(), 1, vec![attr("background", "bronze", None)]).unwrap_err();
+    assert_eq!(
+        err,
+        invalid("background", "value \"bronze\" is not allowed")
+    );
+    for bad in ["fast", "1.", ".5", "-", "1e3"] {
+        let err = mint(
+            deps.as_mut(),
+            1,
+            vec![
+                attr("background", "gold", None),
+                attr("speed", bad, Some("boost_number")),
+            ],
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidTrait { .. }));
+    }
+    let err = mint(
+        deps.as_mut(),
+        1,
+        vec![
+            attr("background", "gold", None),
+            attr("speed", "10", Some("boost_percentage")),
+        ],
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        invalid(
+            "speed",
+            "display type Some(\"boost_percentage\") is not allowed"
+        )
+    );
+    let err = mint(
+        deps.as_mut(),
+        1,
+        vec![attr("background", "gold", None), attr("born", "-5", None)],
+    )
+    .unwrap_err();
+    assert_eq!(err, invalid("born", "value \"-5\" is not allowed"));
+
+    // a trait type may only appear once
+    let err = mint(
+        deps.as_mut(),
+        1,
+        vec![
+            attr("background", "gold", None),
+            attr("background", "silver", None),
+        ],
+    )
+    .unwrap_err();
+    assert_eq!(err, invalid("background", "duplicate trait"));
+
+    mint(
+        deps.as_mut(),
+        1,
+        vec![
+            attr("background", "gold", None),
+            attr("speed", "-1.5", Some("boost_number")),
+            attr("born", "1546360800", None),
+        ],
+    )
+    .unwrap();
+
+    // metadata updates are held to the same schema
+    let mut extension = Metadata::new_test();
+    extension.attributes = vec![attr("background", "bronze", None)];
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::UpdateMetadata {
+                token_id: TokenId::new(1),
+                token_uri: None,
+                extension: Some(extension),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        invalid("background", "value \"bronze\" is not allowed")
+    );
+}
+
+#[test]
+fn metadata_validation() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = DummyNftContract::default();
+
+    let limits = MetadataLimits {
+        max_field_length: 64,
+        max_metadata_size: 512,
+        max_attributes: 3,
+    };
+    let msg = InstantiateMsg {
+        name: CONTRACT_NAME.to_string(),
+        symbol: SYMBOL.to_string(),
+        minter: String::from(MINTER),
+        metadata_limits: Some(limits.clone()),
+        ..Default::default()
+    };
+    contract
+        .instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg)
+        .unwrap();
+    let res: MetadataLimitsResponse = from_binary(
+        &contract
+            .query(deps.as_ref(), mock_env(), QueryMsg::MetadataLimits {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.limits, limits);
+
+    let mint = |deps: DepsMut, extension: Metadata| {
+        let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
+            token_id: Some(TokenId::new(1)),
+            owner: String::from("venus"),
+            token_uri: None,
+            extension,
+            royalty: None,
+            transferable: None,
+        }));
+        contract.execute(deps, mock_env(), mock_info(MINTER, &[]), mint_msg)
+    };
+    let attr = |value: &str| Trait {
+        display_type: None,
+        trait_type: String::from("background"),
+        value: value.to_string(),
+    };
+
+    // only ipfs, arweave and https URIs are accepted
+    for (image, valid) in [
+        ("ipfs://deadbeef", true),
+        ("ar://deadbeef", true),
+        ("https://example.com/1.png", true),
+        ("http://example.com/1.png", false),
+        ("ipfs://", false),
+        ("deadbeef", false),
+        ("", true),
+    ] {
+        let mut extension = Metadata::new_test();
+        extension.image = image.to_owned();
+        let err = contract
+            .check_metadata(deps.as_ref().storage, &extension)
+            .err();
+        let expected = ContractError::InvalidUri {
+            field: String::from("image"),
+            uri: image.to_owned(),
+        };
+        assert_eq!(err, if valid { None } else { Some(expected) });
+    }
+    let mut extension = Metadata::new_test();
+    extension.youtube_url = Some(String::from("javascript:alert(1)"));
+    let err = mint(deps.as_mut(), extension).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidUri { field, .. } if field == "youtube_url"));
+
+    // an on-chain SVG needs no image URI
+    let mut extension = Metadata::new_test();
+    extension.image = String::new();
+    extension.image_data = Some(String::from("<svg/>"));
+    contract
+        .check_metadata(deps.as_ref().storage, &extension)
+        .unwrap();
+
+    for (color, valid) in [
+        ("00ffAA", true),
+        ("#00ffaa", false),
+        ("fff", false),
+        ("00ffag", false),
+    ] {
+        let mut extension = Metadata::new_test();
+        extension.background_color = Some(color.to_owned());
+        let err = contract
+            .check_metadata(deps.as_ref().storage, &extension)
+            .err();
+        let expected = ContractError::InvalidBackgroundColor {
+            color: color.to_owned(),
+        };
+        assert_eq!(err, if valid { None } else { Some(expected) });
+    }
+
+    let mut extension = Metadata::new_test();
+    extension.image_data = Some("x".repeat(65));
+    let err = mint(deps.as_mut(), extension).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::FieldTooLong {
+            field: String::from("image_data"),
+            max: 64
+        }
+    );
+    let mut extension = Metadata::new_test();
+    extension.attributes = vec![attr(&"x".repeat(65))];
+    let err = mint(deps.as_mut(), extension).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::FieldTooLong {
+            field: String::from("attributes.value"),
+            max: 64
+        }
+    );
+
+    let mut extension = Metadata::new_test();
+    extension.attributes = vec![attr("gold"); 4];
+    let err = mint(deps.as_mut(), extension).unwrap_err();
+    assert_eq!(err, ContractError::TooManyAttributes { count: 4, max: 3 });
+
+    // every field fits on its own, but not all of them together
+    let mut extension = Metadata::new_test();
+    extension.description = "x".repeat(64);
+    extension.name = "x".repeat(64);
+    extension.image_data = Some("x".repeat(64));
+    extension.attributes = vec![attr(&"x".repeat(64)); 3];
+    let err = mint(deps.as_mut(), extension).unwrap_err();
+    assert!(matches!(
+        err,
+        ContractError::MetadataTooLarge { max: 512, .. }
+    ));
+
+    mint(deps.as_mut(), Metadata::new_test()).unwrap();
+
+    // metadata updates are held to the same limits
+    let mut extension = Metadata::new_test();
+    extension.external_url = Some(String::from("ftp://example.com"));
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::UpdateMetadata {
+                token_id: TokenId::new(1),
+                token_uri: None,
+                extension: Some(extension),
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::InvalidUri { field, .. } if field == "external_url"));
+}
+
+#[test]
+fn base_token_uri() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+
+    let minter = mock_info(MINTER, &[]);
+    for (token_id, token_uri) in [(1, None), (2, Some("https://example.com/2.json"))] {
+        let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
+            token_id: Some(TokenId::new(token_id)),
+            owner: String::from("venus"),
+            token_uri: token_uri.map(String::from),
+            extension: Metadata::new_test(),
+            royalty: None,
+            transferable: None,
+        }));
+        contract
+            .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+            .unwrap();
+    }
+    let token_uri = |deps: Deps, token_id: u64| {
+        let info = contract.nft_info(deps, TokenId::new(token_id)).unwrap();
+        let all_info = contract
+            .all_nft_info(deps, mock_env(), TokenId::new(token_id), false)
+            .unwrap();
+        assert_eq!(info.token_uri, all_info.info.token_uri);
+        info.token_uri
+    };
+    assert_eq!(token_uri(deps.as_ref(), 1), None);
+
+    let set_base_uri = |base_uri: Option<&str>, suffix: Option<&str>| ExecuteMsg::SetBaseUri {
+        base_uri: base_uri.map(String::from),
+        token_uri_suffix: suffix.map(String::from),
+    };
+
+    // only the minter can set the base URI
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[]),
+            set_base_uri(Some("ipfs://cid/"), None),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            set_base_uri(Some("ipfs://cid/"), Some(".json")),
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "set_base_uri")
+            .add_attribute("sender", MINTER)
+            .add_attribute("base_uri", "ipfs://cid/")
+    );
+    let info = contract.contract_info(deps.as_ref()).unwrap();
+    assert_eq!(info.base_uri, Some(String::from("ipfs://cid/")));
+    assert_eq!(info.token_uri_suffix, Some(String::from(".json")));
+
+    // explicit URIs are kept
+    assert_eq!(
+        token_uri(deps.as_ref(), 1),
+        Some(String::from("ipfs://cid/1.json"))
+    );
+    assert_eq!(
+        token_uri(deps.as_ref(), 2),
+        Some(String::from("https://example.com/2.json"))
+    );
+
+    // freezing a token with an explicit URI does not lock the base URI
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::FreezeMetadata {
+                token_id: TokenId::new(2),
+            },
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            set_base_uri(Some("ar://tx/"), None),
+        )
+        .unwrap();
+    assert_eq!(token_uri(deps.as_ref(), 1), Some(String::from("ar://tx/1")));
+
+    // freezing a token relying on the base URI does
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::FreezeMetadata {
+                token_id: TokenId::new(1),
+            },
+        )
+        .unwrap();
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), minter, set_base_uri(None, None))
+        .unwrap_err();
+    assert_eq!(err, ContractError::BaseUriFrozen {});
+    assert_eq!(token_uri(deps.as_ref(), 1), Some(String::from("ar://tx/1")));
+
+    // burning the token releases the base URI again
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[]),
+            ExecuteMsg::Burn {
+                token_id: TokenId::new(1),
+            },
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            set_base_uri(None, None),
+        )
+        .unwrap();
+    assert_eq!(
+        token_uri(deps.as_ref(), 2),
+        Some(String::from("https://example.com/2.json"))
+    );
+}
+
+#[test]
+fn use_metadata_extension() {
+    const CREATOR: &str = "creator";
+
+    let mut deps = mock_dependencies(&[]);
+    let contract = DummyNftContract::default();
+
+    let info = mock_info(CREATOR, &[]);
+    let init_msg = InstantiateMsg {
+        name: "SpaceShips".to_string(),
+        symbol: "SPACE".to_string(),
+        minter: CREATOR.to_string(),
+        ..Default::default()
+    };
+    contract
+        .instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg)
+        .unwrap();
+
+    let token_id = TokenId::new(1701);
+    let mint_msg = MintMsg {
+        token_id: Some(token_id),
+        owner: "john".to_string(),
+        token_uri: Some("https://starships.example.com/Starship/Enterprise.json".into()),
+        extension: Metadata::new_test(),
+        royalty: None,
+        transferable: None,
+    };
+    let exec_msg = ExecuteMsg::Mint(Box::new(mint_msg.clone()));
+    contract
+        .execute(deps.as_mut(), mock_env(), info, exec_msg)
+        .unwrap();
+
+    let res = contract.nft_info(deps.as_ref(), token_id).unwrap();
+    assert_eq!(res.token_uri, mint_msg.token_uri);
+    assert_eq!(res.extension, mint_msg.extension);
+}
+
+#[test]
+fn updating_metadata() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+
+    let token_id = TokenId::new(12);
+    let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
+        token_id: Some(token_id),
+        owner: String::from("medusa"),
+        token_uri: Some("https://gateway.example.com/12.json".to_owned()),
+        extension: Metadata::new_test(),
+        royalty: None,
+        transferable: None,
+    }));
+    contract
+        .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), mint_msg)
+        .unwrap();
+
+    let mut extension = Metadata::new_test();
+    extension.description = "fixed desc".to_owned();
+    extension.image = "ipfs://cafebabe".to_owned();
+    let update_msg = ExecuteMsg::UpdateMetadata {
+        token_id,
+        token_uri: None,
+        extension: Some(extension.clone()),
+    };
+
+    // neither the owner nor random accounts can edit
+    for sender in ["medusa", "random"] {
+        let err = contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(sender, &[]),
+                update_msg.clone(),
+            )
+            .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+    }
+
+    // the minter can, and only the given fields change
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            update_msg,
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "update_metadata")
+            .add_attribute("sender", MINTER)
+            .add_attribute("token_id", token_id)
+            .add_attribute("changed", "image,description")
+    );
+    assert_eq!(
+        contract.nft_info(deps.as_ref(), token_id).unwrap(),
+        NftInfoResponse {
+            token_uri: Some("https://gateway.example.com/12.json".to_owned()),
+            extension: extension.clone(),
+            transferable: true,
+        }
+    );
+
+    // so can a metadata editor
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::GrantRole {
+                role: Role::MetadataEditor,
+                address: String::from("editor"),
+            },
+        )
+        .unwrap();
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("editor", &[]),
+            ExecuteMsg::UpdateMetadata {
+                token_id,
+                token_uri: Some("ipfs://cafebabe/12.json".to_owned()),
+                extension: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.attributes[3].value, "token_uri");
+    assert_eq!(
+        contract.nft_info(deps.as_ref(), token_id).unwrap(),
+        NftInfoResponse {
+            token_uri: Some("ipfs://cafebabe/12.json".to_owned()),
+            extension,
+            transferable: true,
+        }
+    );
+
+    // unknown tokens cannot be edited
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("editor", &[]),
+            ExecuteMsg::UpdateMetadata {
+                token_id: TokenId::new(13),
+                token_uri: None,
+                extension: None,
+            },
+        )
+        .unwrap_err();
+}
+
+#[test]
+fn freezing_metadata() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER, &[]);
+
+    let mint = |token_id: u64| {
+        ExecuteMsg::Mint(Box::new(MintMsg {
+            token_id: Some(TokenId::new(token_id)),
+            owner: String::from("medusa"),
+            token_uri: None,
+            extension: Metadata::new_test(),
+            royalty: None,
+            transferable: None,
+        }))
+    };
+    let update = |token_id: u64| ExecuteMsg::UpdateMetadata {
+        token_id: TokenId::new(token_id),
+        token_uri: Some("ipfs://cafebabe".to_owned()),
+        extension: None,
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint(1))
+        .unwrap();
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint(2))
+        .unwrap();
+
+    // only editors can freeze, and only existing tokens
+    let freeze_msg = ExecuteMsg::FreezeMetadata {
+        token_id: TokenId::new(1),
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("medusa", &[]),
+            freeze_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::FreezeMetadata {
+                token_id: TokenId::new(3),
+            },
+        )
+        .unwrap_err();
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), freeze_msg)
+        .unwrap();
+
+    // a frozen token cannot be edited, others still can
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), update(1))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::MetadataFrozen {
+            token_id: TokenId::new(1)
+        }
+    );
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), update(2))
+        .unwrap();
+    assert_eq!(
+        contract
+            .freeze_status(deps.as_ref(), Some(TokenId::new(2)))
+            .unwrap(),
+        FreezeStatusResponse {
+            all_frozen: false,
+            token_frozen: Some(false),
+        }
+    );
+
+    // freezing everything covers existing and future tokens
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::FreezeAllMetadata {},
+        )
+        .unwrap();
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint(3))
+        .unwrap();
+    for token_id in [2, 3] {
+        let err = contract
+            .execute(deps.as_mut(), mock_env(), minter.clone(), update(token_id))
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::MetadataFrozen {
+                token_id: TokenId::new(token_id)
+            }
+        );
+    }
+
+    // and survives a migration
+    let migrate_msg = MigrateMsg::default();
+    contract.migrate(deps.as_mut(), migrate_msg).unwrap();
+    assert_eq!(
+        contract
+            .freeze_status(deps.as_ref(), Some(TokenId::new(2)))
+            .unwrap(),
+        FreezeStatusResponse {
+            all_frozen: true,
+            token_frozen: Some(true),
+        }
+    );
+}
+
+#[test]
+fn royalties() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = DummyNftContract::default();
+    let instantiate_msg = |share: &str| InstantiateMsg {
+        name: CONTRACT_NAME.to_string(),
+        symbol: SYMBOL.to_string(),
+        minter: String::from(MINTER),
+        royalty: Some(Royalty {
+            payment_address: String::from("artist"),
+            share: share.parse().unwrap(),
+        }),
+        ..Default::default()
+    };
+
+    // shares above 100% are rejected
+    let err = contract
+        .instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg("1.01"),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidRoyaltyShare {
+            share: Decimal::percent(101)
+        }
+    );
+    contract
+        .instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg("0.05"),
+        )
+        .unwrap();
+    assert_eq!(
+        contract.check_royalties(deps.as_ref()).unwrap(),
+        CheckRoyaltiesResponse {
+            royalty_payments: true
+        }
+    );
+
+    let mint = |token_id: u64, royalty: Option<(&str, &str)>| {
+        ExecuteMsg::Mint(Box::new(MintMsg {
+            token_id: Some(TokenId::new(token_id)),
+            owner: String::from("medusa"),
+            token_uri: None,
+            extension: Metadata::new_test(),
+            royalty: royalty.map(|(payment_address, share)| Royalty {
+                payment_address: payment_address.to_owned(),
+                share: share.parse().unwrap(),
+            }),
+            transferable: None,
+        }))
+    };
+    let minter = mock_info(MINTER, &[]);
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint(1, None))
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            mint(2, Some(("collab", "0.1"))),
+        )
+        .unwrap();
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter,
+            mint(3, Some(("collab", "1.5"))),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidRoyaltyShare {
+            share: "1.5".parse().unwrap()
+        }
+    );
+
+    // the default applies unless overridden
+    assert_eq!(
+        contract
+            .royalty_info(deps.as_ref(), TokenId::new(1), Uint128::new(1000))
+            .unwrap(),
+        RoyaltiesInfoResponse {
+            address: String::from("artist"),
+            royalty_amount: Uint128::new(50),
+        }
+    );
+    assert_eq!(
+        contract
+            .royalty_info(deps.as_ref(), TokenId::new(2), Uint128::new(1000))
+            .unwrap(),
+        RoyaltiesInfoResponse {
+            address: String::from("collab"),
+            royalty_amount: Uint128::new(100),
+        }
+    );
+    contract
+        .royalty_info(deps.as_ref(), TokenId::new(3), Uint128::new(1000))
+        .unwrap_err();
+
+    // without any royalty configured nothing is owed
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            mint(1, None),
+        )
+        .unwrap();
+    assert_eq!(
+        contract
+            .royalty_info(deps.as_ref(), TokenId::new(1), Uint128::new(1000))
+            .unwrap(),
+        RoyaltiesInfoResponse {
+            address: String::new(),
+            royalty_amount: Uint128::zero(),
+        }
+    );
+}
+
+#[test]
+fn soulbound_tokens() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER, &[]);
+    let owner = mock_info("medusa", &[]);
+
+    let mint = |token_id: u64, transferable: Option<bool>| {
+        ExecuteMsg::Mint(Box::new(MintMsg {
+            token_id: Some(TokenId::new(token_id)),
+            owner: String::from("medusa"),
+            token_uri: None,
+            extension: Metadata::new_test(),
+            royalty: None,
+            transferable,
+        }))
+    };
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            mint(1, Some(false)),
+        )
+        .unwrap();
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint(2, None))
+        .unwrap();
+    assert!(
+        !contract
+            .nft_info(deps.as_ref(), TokenId::new(1))
+            .unwrap()
+            .transferable
+    );
+    assert!(
+        contract
+            .nft_info(deps.as_ref(), TokenId::new(2))
+            .unwrap()
+            .transferable
+    );
+
+    // the badge cannot move or be approved
+    let badge = TokenId::new(1);
+    let msgs = vec![
+        ExecuteMsg::TransferNft {
+            recipient: String::from("random"),
+            token_id: badge,
+        },
+        ExecuteMsg::SendNft {
+            contract: String::from("another_contract"),
+            token_id: badge,
+            msg: to_binary("hello").unwrap(),
+            return_on_failure: None,
+        },
+        ExecuteMsg::Approve {
+            spender: String::from("random"),
+            token_id: badge,
+            expires: None,
+        },
+    ];
+    for msg in msgs {
+        let err = contract
+            .execute(deps.as_mut(), mock_env(), owner.clone(), msg)
+            .unwrap_err();
+        assert_eq!(err, ContractError::NonTransferable { token_id: badge });
+    }
+
+    // operators cannot burn the badge on the owner's behalf
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner.clone(),
+            ExecuteMsg::ApproveAll {
+                operator: String::from("random"),
+                expires: None,
+            },
+        )
+        .unwrap();
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            ExecuteMsg::Burn { token_id: badge },
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // other tokens are unaffected and the owner can still burn the badge
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner.clone(),
+            ExecuteMsg::TransferNft {
+                recipient: String::from("random"),
+                token_id: TokenId::new(2),
+            },
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner.clone(),
+            ExecuteMsg::Burn { token_id: badge },
+        )
+        .unwrap();
+
+    // in a soulbound collection operators can only move tokens that opted out
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        name: CONTRACT_NAME.to_string(),
+        symbol: SYMBOL.to_string(),
+        minter: String::from(MINTER),
+        transferable: Some(false),
+        ..Default::default()
+    };
+    contract
+        .instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg)
+        .unwrap();
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint(1, None))
+        .unwrap();
+    contract
+        .execute(deps.as_mut(), mock_env(), minter, mint(2, Some(true)))
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner,
+            ExecuteMsg::ApproveAll {
+                operator: String::from("random"),
+                expires: None,
+            },
+        )
+        .unwrap();
+    let operator = mock_info("random", &[]);
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            operator.clone(),
+            ExecuteMsg::TransferNft {
+                recipient: String::from("random"),
+                token_id: TokenId::new(1),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::NonTransferable {
+            token_id: TokenId::new(1)
+        }
+    );
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            operator,
+            ExecuteMsg::TransferNft {
+                recipient: String::from("random"),
+                token_id: TokenId::new(2),
+            },
+        )
+        .unwrap();
+}
+
+#[test]
+fn burn_and_reuse() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+
+    let token_id = TokenId::new(1);
+    let token_uri = "https://www.merriam-webster.com/dictionary/petrify".to_string();
+
+    let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
+        token_id: Some(token_id),
+        owner: MINTER.to_string(),
+        token_uri: Some(token_uri),
+        extension: Metadata::new_test(),
+        royalty: None,
+        transferable: None,
+    }));
+
+    let burn_msg = ExecuteMsg::Burn { token_id };
+
+    // mint some NFT
+    let allowed = mock_info(MINTER, &[]);
+    let _ = contract
+        .execute(deps.as_mut(), mock_env(), allowed.clone(), mint_msg.clone())
+        .unwrap();
+
+    // Burn it
+    let _ = contract
+        .execute(deps.as_mut(), mock_env(), allowed.clone(), burn_msg)
+        .unwrap();
+
+    // Cannot remint
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), allowed, mint_msg)
+        .unwrap_err();
+
+    assert_eq!(err, ContractError::RemintBurned { token_id });
+}
+
+#[test]
+fn highest_token_id() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+
+    // Starts as None
+    assert_eq!(
+        contract.highest_token_id(deps.as_ref()).unwrap(),
+        HighestTokenIdResponse {
+            highest_token_id: None
+        }
+    );
+
+    let token_id = TokenId::new(1);
+    let token_uri = "https://www.merriam-webster.com/dictionary/petrify".to_string();
+    let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
+        token_id: Some(token_id),
+        owner: MINTER.to_string(),
+        token_uri: Some(token_uri),
+        extension: Metadata::new_test(),
+        royalty: None,
+        transferable: None,
+    }));
+    let allowed = mock_info(MINTER, &[]);
+    let _ = contract
+        .execute(deps.as_mut(), mock_env(), allowed.clone(), mint_msg.clone())
+        .unwrap();
+
+    // ID has been updated
+    assert_eq!(
+        contract.highest_token_id(deps.as_ref()).unwrap(),
+        HighestTokenIdResponse {
+            highest_token_id: Some(token_id)
+        }
+    );
+
+    // Burning does not affect it
+    let burn_msg = ExecuteMsg::Burn { token_id };
+    let _ = contract
+        .execute(deps.as_mut(), mock_env(), allowed.clone(), burn_msg)
+        .unwrap();
+    assert_eq!(
+        contract.highest_token_id(deps.as_ref()).unwrap(),
+        HighestTokenIdResponse {
+            highest_token_id: Some(token_id),
+        }
+    );
+
+    // Minting again updates
+    let token_id = TokenId::new(5);
+    let token_uri = "https://www.merriam-webster.com/dictionary/petrify".to_string();
+    let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
+        token_id: Some(token_id),
+        owner: MINTER.to_string(),
+        token_uri: Some(token_uri),
+        extension: Metadata::new_test(),
+        royalty: None,
+        transferable: None,
+    }));
+    let allowed = mock_info(MINTER, &[]);
+    let _ = contract
+        .execute(deps.as_mut(), mock_env(), allowed.clone(), mint_msg.clone())
+        .unwrap();
+    assert_eq!(
+        contract.highest_token_id(deps.as_ref()).unwrap(),
+        HighestTokenIdResponse {
+            highest_token_id: Some(token_id),
+        }
+    );
+
+    // Minting lower has no affect updates
+    let lower_token_id = TokenId::new(4);
+    let token_uri = "https://www.merriam-webster.com/dictionary/petrify".to_string();
+    let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
+        token_id: Some(lower_token_id),
+        owner: MINTER.to_string(),
+        token_uri: Some(token_uri),
+        extension: Metadata::new_test(),
+        royalty: None,
+        transferable: None,
+    }));
+    let allowed = mock_info(MINTER, &[]);
+    let _ = contract
+        .execute(deps.as_mut(), mock_env(), allowed.clone(), mint_msg.clone())
+        .unwrap();
+    assert_eq!(
+        contract.highest_token_id(deps.as_ref()).unwrap(),
+        HighestTokenIdResponse {
+            highest_token_id: Some(token_id),
+        }
+    );
+}
+#[test]
+fn minter_handoff() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+
+    const NEW_MINTER: &str = "newminter";
+    let allowed = mock_info(MINTER, &[]);
+    let next_allowed = mock_info(NEW_MINTER, &[]);
+
+    // nothing to accept or cancel yet
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            next_allowed.clone(),
+            ExecuteMsg::AcceptMinter {},
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::NoPendingMinter {});
+
+    // only the minter can propose
+    let propose_msg = ExecuteMsg::ProposeMinter {
+        new_minter: String::from(NEW_MINTER),
+        expires: Some(Expiration::AtHeight(mock_env().block.height + 10)),
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            next_allowed.clone(),
+            propose_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            allowed.clone(),
+            propose_msg.clone(),
+        )
+        .unwrap();
+    assert_eq!(
+        contract.pending_minter(deps.as_ref()).unwrap(),
+        PendingMinterResponse {
+            pending_minter: Some(String::from(NEW_MINTER)),
+            expires: Some(Expiration::AtHeight(mock_env().block.height + 10)),
+        }
+    );
+
+    // the proposal can be cancelled
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            allowed.clone(),
+            ExecuteMsg::CancelMinterProposal {},
+        )
+        .unwrap();
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            next_allowed.clone(),
+            ExecuteMsg::AcceptMinter {},
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::NoPendingMinter {});
+
+    // only the proposed minter can accept, and only before expiry
+    contract
+        .execute(deps.as_mut(), mock_env(), allowed.clone(), propose_msg)
+        .unwrap();
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            ExecuteMsg::AcceptMinter {},
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let mut late_env = mock_env();
+    late_env.block.height += 10;
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            late_env,
+            next_allowed.clone(),
+            ExecuteMsg::AcceptMinter {},
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::MinterProposalExpired {});
+
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            next_allowed,
+            ExecuteMsg::AcceptMinter {},
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "accept_minter")
+            .add_attribute("minter", NEW_MINTER)
+    );
+    assert_eq!(NEW_MINTER, contract.minter(deps.as_ref()).unwrap().minter);
+    assert_eq!(
+        contract.pending_minter(deps.as_ref()).unwrap(),
+        PendingMinterResponse {
+            pending_minter: None,
+            expires: None,
+        }
+    );
+
+    // the old minter has lost its rights
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            allowed,
+            ExecuteMsg::CancelMinterProposal {},
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+}
+
+#[test]
+fn role_management() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+
+    // the minter is admin and minter by default
+    assert_eq!(
+        contract
+            .role_holders(deps.as_ref(), Role::Admin, None, None)
+            .unwrap(),
+        RoleHoldersResponse {
+            holders: vec![String::from(MINTER)]
+        }
+    );
+    assert_eq!(
+        contract
+            .role_holders(deps.as_ref(), Role::Minter, None, None)
+            .unwrap(),
+        RoleHoldersResponse {
+            holders: vec![String::from(MINTER)]
+        }
+    );
+
+    let mint_msg = |token_id: u64| {
+        ExecuteMsg::Mint(Box::new(MintMsg {
+            token_id: Some(TokenId::new(token_id)),
+            owner: String::from("someowner"),
+            token_uri: None,
+            extension: Metadata::new_test(),
+            royalty: None,
+            transferable: None,
+        }))
+    };
+    let sale = mock_info("sale", &[]);
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), sale.clone(), mint_msg(1))
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // only admins can grant roles
+    let grant_msg = ExecuteMsg::GrantRole {
+        role: Role::Minter,
+        address: String::from("sale"),
+    };
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), sale.clone(), grant_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), grant_msg)
+        .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "grant_role")
+            .add_attribute("sender", MINTER)
+            .add_attribute("role", "minter")
+            .add_attribute("address", "sale")
+    );
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::GrantRole {
+                role: Role::Minter,
+                address: String::from("airdrop"),
+            },
+        )
+        .unwrap();
+
+    // all minters can mint, the primary minter is unchanged
+    contract
+        .execute(deps.as_mut(), mock_env(), sale.clone(), mint_msg(1))
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("airdrop", &[]),
+            mint_msg(2),
+        )
+        .unwrap();
+    assert_eq!(MINTER, contract.minter(deps.as_ref()).unwrap().minter);
+
+    // paginate the holders
+    let holders = contract
+        .role_holders(deps.as_ref(), Role::Minter, None, Some(2))
+        .unwrap();
+    assert_eq!(holders.holders, vec!["airdrop", MINTER]);
+    let holders = contract
+        .role_holders(
+            deps.as_ref(),
+            Role::Minter,
+            Some(String::from(MINTER)),
+            None,
+        )
+        .unwrap();
+    assert_eq!(holders.holders, vec!["sale"]);
+
+    // revoked minters can no longer mint
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::RevokeRole {
+                role: Role::Minter,
+                address: String::from("sale"),
+            },
+        )
+        .unwrap();
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), sale, mint_msg(3))
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // admins cannot lock themselves out
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::RevokeRole {
+                role: Role::Admin,
+                address: String::from(MINTER),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::CannotRevokeOwnAdmin {});
+
+    // a primary minter whose role was revoked cannot hand it back to itself or a friend
+    let minter = mock_info(MINTER, &[]);
+    let admin = mock_info("boss", &[]);
+    let propose_msg = |new_minter: &str| ExecuteMsg::ProposeMinter {
+        new_minter: String::from(new_minter),
+        expires: None,
+    };
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::GrantRole {
+                role: Role::Admin,
+                address: String::from("boss"),
+            },
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            admin.clone(),
+            ExecuteMsg::RevokeRole {
+                role: Role::Admin,
+                address: String::from(MINTER),
+            },
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            propose_msg("friend"),
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            admin,
+            ExecuteMsg::RevokeRole {
+                role: Role::Minter,
+                address: String::from(MINTER),
+            },
+        )
+        .unwrap();
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("friend", &[]),
+            ExecuteMsg::AcceptMinter {},
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::NoPendingMinter {});
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            propose_msg(MINTER),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), minter, mint_msg(3))
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+}
+
+#[test]
+fn pausing() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+
+    let mint_msg = |token_id: u64| {
+        ExecuteMsg::Mint(Box::new(MintMsg {
+            token_id: Some(TokenId::new(token_id)),
+            owner: String::from("venus"),
+            token_uri: None,
+            extension: Metadata::new_test(),
+            royalty: None,
+            transferable: None,
+        }))
+    };
+    let transfer_msg = |token_id: u64| ExecuteMsg::TransferNft {
+        recipient: String::from("mars"),
+        token_id: TokenId::new(token_id),
+    };
+    let minter = mock_info(MINTER, &[]);
+    let venus = mock_info("venus", &[]);
+    let guard = mock_info("guard", &[]);
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg(1))
+        .unwrap();
+
+    // only pausers can pause, even admins need the role first
+    let pause_msg = |scope: PauseScope| ExecuteMsg::Pause { scope };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            pause_msg(PauseScope::Mint),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::GrantRole {
+                role: Role::Pauser,
+                address: String::from("guard"),
+            },
+        )
+        .unwrap();
+
+    // pausing mints leaves transfers alone
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            guard.clone(),
+            pause_msg(PauseScope::Mint),
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "pause")
+            .add_attribute("sender", "guard")
+            .add_attribute("scope", "mint")
+    );
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg(2))
+        .unwrap_err();
+    assert_eq!(err, ContractError::Paused {});
+    contract
+        .execute(deps.as_mut(), mock_env(), venus.clone(), transfer_msg(1))
+        .unwrap();
+
+    // pausing transfers as well widens the pause to everything
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            guard.clone(),
+            pause_msg(PauseScope::Transfer),
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "pause")
+            .add_attribute("sender", "guard")
+            .add_attribute("scope", "all")
+    );
+    let mars = mock_info("mars", &[]);
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), mars.clone(), transfer_msg(1))
+        .unwrap_err();
+    assert_eq!(err, ContractError::Paused {});
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mars.clone(),
+            ExecuteMsg::Burn {
+                token_id: TokenId::new(1),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Paused {});
+
+    // queries keep working
+    let status: PauseStatusResponse = from_binary(
+        &contract
+            .query(deps.as_ref(), mock_env(), QueryMsg::PauseStatus {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        status,
+        PauseStatusResponse {
+            mint_paused: true,
+            transfer_paused: true,
+        }
+    );
+    let owner = contract
+        .owner_of(deps.as_ref(), mock_env(), TokenId::new(1), false)
+        .unwrap();
+    assert_eq!(owner.owner, "mars");
+
+    // unpausing lifts everything
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mars.clone(),
+            ExecuteMsg::Unpause {},
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    contract
+        .execute(deps.as_mut(), mock_env(), guard, ExecuteMsg::Unpause {})
+        .unwrap();
+    contract
+        .execute(deps.as_mut(), mock_env(), minter, mint_msg(2))
+        .unwrap();
+    contract
+        .execute(deps.as_mut(), mock_env(), mars, transfer_msg(1))
+        .unwrap();
+    assert_eq!(
+        contract.pause_status(deps.as_ref()).unwrap(),
+        PauseStatusResponse {
+            mint_paused: false,
+            transfer_paused: false,
+        }
+    );
+}
 
 #[test]
 fn can_migrate() {
@@ -875,10 +4403,12 @@ fn can_migrate() {
 
     // Next minter can't mint, original one can
     let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
-        token_id: TokenId::new(945),
+        token_id: Some(TokenId::new(945)),
         owner: String::from("someowner"),
         token_uri: None,
         extension: Metadata::new_test(),
+        royalty: None,
+        transferable: None,
     }));
     let _ = contract
         .execute(
@@ -899,6 +4429,7 @@ fn can_migrate() {
         name: Some(NEW_NAME.to_owned()),
         symbol: Some(NEW_SYMBOL.to_owned()),
         minter: Some(NEW_MINTER.to_owned()),
+        ..Default::default()
     };
     let _ = contract
         .migrate(deps.as_mut(), migrate_msg.clone())
@@ -910,16 +4441,22 @@ fn can_migrate() {
         info,
         ContractInfoResponse {
             name: NEW_NAME.to_owned(),
-            symbol: NEW_SYMBOL.to_owned()
+            symbol: NEW_SYMBOL.to_owned(),
+            max_supply: None,
+            max_token_id: None,
+            base_uri: None,
+            token_uri_suffix: None,
         }
     );
 
     // Next minter can mint, original one can't
     let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
-        token_id: TokenId::new(946),
+        token_id: Some(TokenId::new(946)),
         owner: String::from("someowner"),
         token_uri: None,
         extension: Metadata::new_test(),
+        royalty: None,
+        transferable: None,
     }));
     let _ = contract
         .execute(deps.as_mut(), mock_env(), allowed.clone(), mint_msg.clone())
@@ -932,8 +4469,8 @@ fn can_migrate() {
     const NEW_NEW_NAME: &str = "newnewname";
     let migrate_msg = MigrateMsg {
         name: Some(NEW_NEW_NAME.to_owned()),
-        symbol: None,
         minter: Some(MINTER.to_owned()),
+        ..Default::default()
     };
     let _ = contract
         .migrate(deps.as_mut(), migrate_msg.clone())
@@ -945,16 +4482,22 @@ fn can_migrate() {
         info,
         ContractInfoResponse {
             name: NEW_NEW_NAME.to_owned(),
-            symbol: NEW_SYMBOL.to_owned()
+            symbol: NEW_SYMBOL.to_owned(),
+            max_supply: None,
+            max_token_id: None,
+            base_uri: None,
+            token_uri_suffix: None,
         }
     );
 
     // Next minter can't mint, original one can
     let mint_msg = ExecuteMsg::Mint(Box::new(MintMsg {
-        token_id: TokenId::new(947),
+        token_id: Some(TokenId::new(947)),
         owner: String::from("someowner"),
         token_uri: None,
         extension: Metadata::new_test(),
+        royalty: None,
+        transferable: None,
     }));
     let _ = contract
         .execute(deps.as_mut(), mock_env(), next_allowed, mint_msg.clone())
@@ -6,14 +6,22 @@ mod types;
 pub use cw0::Expiration;
 
 pub use types::query::{
-    AllNftInfoResponse, Approval, ApprovedForAllResponse, ContractInfoResponse,
-    HighestTokenIdResponse, MinterResponse, NftInfoResponse, NumTokensResponse, OwnerOfResponse,
-    QueryMsg, TokensResponse,
+    AllNftInfoResponse, Approval, ApprovalLimitResponse, ApprovalResponse, ApprovalsResponse,
+    ApprovedForAllResponse, BalanceResponse, CanTransferResponse, CheckRoyaltiesResponse,
+    ContractInfoResponse, FreezeStatusResponse, HighestTokenIdResponse, Holder,
+    MetadataLimitsResponse, MinterResponse, NftInfoResponse, NumTokensResponse, OperatorGrant,
+    OperatorGrantsResponse, OrderBy, OwnerOfResponse, PauseStatusResponse, PendingMinterResponse,
+    QueryMsg, RoleHoldersResponse, RoyaltiesInfoResponse, SupplyInfoResponse, TokenRarityResponse,
+    TokensResponse, TopHoldersResponse, TraitSchemaResponse, TraitStat, TraitStatsResponse,
+    TraitValue, TraitValuesResponse, TransferAuthority, TransferBlock,
 };
 
 pub use types::error::ContractError;
-pub use types::execute::{ExecuteMsg, MintMsg};
+pub use types::execute::{BatchMintResponse, ExecuteMsg, MintMsg, MintResponse, Royalty};
 pub use types::lifecycle::{InstantiateMsg, MigrateMsg};
-pub use types::receiver::ReceiveMsg;
-pub use types::state::{DummyNftContract, Metadata, Trait};
+pub use types::receiver::{BatchReceiveMsg, ReceiveMsg};
+pub use types::state::{
+    DummyNftContract, Metadata, MetadataLimits, PauseScope, Role, Trait, TraitRule, TraitSchema,
+    ValueRule,
+};
 pub use types::token_id::TokenId;
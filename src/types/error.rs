@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Decimal, StdError};
 use thiserror::Error;
 
 use crate::TokenId;
@@ -11,8 +11,8 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
-    #[error("token_id already claimed")]
-    Claimed {},
+    #[error("token_id already claimed: {}", token_id)]
+    Claimed { token_id: TokenId },
 
     #[error("Cannot set approval that is already expired")]
     Expired {},
@@ -22,4 +22,78 @@ pub enum ContractError {
 
     #[error("The given token does not exist: {}", token_id)]
     NoSuchToken { token_id: TokenId },
+
+    #[error("Batch must contain at least one entry")]
+    EmptyBatch {},
+
+    #[error("Batch of {} entries exceeds the maximum of {}", size, max)]
+    BatchTooLarge { size: usize, max: u32 },
+
+    #[error("No token IDs are left to assign")]
+    TokenIdsExhausted {},
+
+    #[error("Maximum supply of {} tokens has been reached", max_supply)]
+    MaxSupplyReached { max_supply: u64 },
+
+    #[error("Token ID {} is above the maximum of {}", token_id, max_token_id)]
+    TokenIdTooHigh {
+        token_id: TokenId,
+        max_token_id: TokenId,
+    },
+
+    #[error("There is no pending minter proposal")]
+    NoPendingMinter {},
+
+    #[error("The minter proposal has expired")]
+    MinterProposalExpired {},
+
+    #[error("Admins cannot revoke their own admin role")]
+    CannotRevokeOwnAdmin {},
+
+    #[error("Metadata of token {} is frozen", token_id)]
+    MetadataFrozen { token_id: TokenId },
+
+    #[error("Royalty share of {} is above 100%", share)]
+    InvalidRoyaltyShare { share: Decimal },
+
+    #[error("Token {} cannot be transferred", token_id)]
+    NonTransferable { token_id: TokenId },
+
+    #[error("Contract is paused")]
+    Paused {},
+
+    #[error("A token cannot hold more than {} approvals", max)]
+    TooManyApprovals { max: u32 },
+
+    #[error("Invalid trait {}: {}", trait_type, reason)]
+    InvalidTrait { trait_type: String, reason: String },
+
+    #[error("{} must be an ipfs://, ar:// or https:// URI: {}", field, uri)]
+    InvalidUri { field: String, uri: String },
+
+    #[error(
+        "Background color must be six hex digits without a leading #: {}",
+        color
+    )]
+    InvalidBackgroundColor { color: String },
+
+    #[error("{} is longer than the maximum of {} bytes", field, max)]
+    FieldTooLong { field: String, max: u32 },
+
+    #[error("Metadata of {} bytes exceeds the maximum of {}", size, max)]
+    MetadataTooLarge { size: usize, max: u32 },
+
+    #[error("{} attributes exceed the maximum of {}", count, max)]
+    TooManyAttributes { count: usize, max: u32 },
+
+    #[error("Base URI cannot change while frozen token metadata relies on it")]
+    BaseUriFrozen {},
+
+    #[error("Batch entry {} failed: {}", index, source)]
+    BatchEntry {
+        index: usize,
+        /// The token ID the entry asked for, unset if it was to be assigned
+        token_id: Option<TokenId>,
+        source: Box<ContractError>,
+    },
 }
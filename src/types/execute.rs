@@ -1,9 +1,10 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::Binary;
+use cosmwasm_std::{Api, Binary, Decimal};
 
-use crate::{Expiration, Metadata, TokenId};
+use crate::types::state;
+use crate::{ContractError, Expiration, Metadata, PauseScope, Role, TokenId};
 
 /// This is like Cw721ExecuteMsg but we add a Mint command for an owner
 /// to make this stand-alone. You will likely want to remove mint and
@@ -16,12 +17,30 @@ pub enum ExecuteMsg {
         recipient: String,
         token_id: TokenId,
     },
+    /// Move several tokens to the same account in one go, bounded by the max batch size.
+    /// Fails as a whole if any single transfer is not allowed
+    BatchTransferNft {
+        recipient: String,
+        token_ids: Vec<TokenId>,
+    },
+    /// Like BatchTransferNft, but every token can go to a different account
+    TransferNftMany { transfers: Vec<(String, TokenId)> },
     /// Send is a base message to transfer a token to a contract and trigger an action
     /// on the receiving contract.
+    /// If `return_on_failure` is set, a failing receiver does not revert the transaction,
+    /// instead the token goes back to its owner along with its approvals.
     SendNft {
         contract: String,
         token_id: TokenId,
         msg: Binary,
+        return_on_failure: Option<bool>,
+    },
+    /// Send several tokens to a contract and trigger a single BatchReceiveNft action
+    /// on the receiving contract, bounded by the max batch size.
+    BatchSendNft {
+        contract: String,
+        token_ids: Vec<TokenId>,
+        msg: Binary,
     },
     /// Allows operator to transfer / send the token from the owner's account.
     /// If expiration is set, then this allowance has a time/height limit
@@ -40,18 +59,72 @@ pub enum ExecuteMsg {
     },
     /// Remove previously granted ApproveAll permission
     RevokeAll { operator: String },
+    /// Remove expired approvals from the given tokens and expired operators from the given
+    /// owners. Anyone can call this. Removes at most `limit` entries, capped by the max batch size
+    PruneExpired {
+        token_ids: Vec<TokenId>,
+        owners: Vec<String>,
+        limit: Option<u32>,
+    },
 
     /// Mint a new NFT, can only be called by the contract minter
     Mint(Box<MintMsg>),
+    /// Mint several NFTs at once, can only be called by the contract minter.
+    /// Either all tokens are minted or none are.
+    BatchMint { mints: Vec<MintMsg> },
 
     /// Burn an NFT the sender has access to
     Burn { token_id: TokenId },
+
+    /// Change the metadata of an existing NFT, can only be called by a minter or a
+    /// metadata editor. Unset fields are left unchanged
+    UpdateMetadata {
+        token_id: TokenId,
+        token_uri: Option<String>,
+        extension: Option<Metadata>,
+    },
+    /// Permanently lock the metadata of one NFT, can only be called by a minter or a
+    /// metadata editor. This cannot be undone
+    FreezeMetadata { token_id: TokenId },
+    /// Permanently lock the metadata of every NFT, including those minted later.
+    /// Can only be called by a minter or a metadata editor. This cannot be undone
+    FreezeAllMetadata {},
+    /// Set the URI prefix of tokens minted without a URI, or clear it by passing none.
+    /// Can only be called by the minter, and not while frozen metadata relies on it
+    SetBaseUri {
+        base_uri: Option<String>,
+        token_uri_suffix: Option<String>,
+    },
+
+    /// Propose handing the minter role to another address, can only be called by the
+    /// contract minter. The new minter has to accept before anything changes.
+    /// If expiration is set, the proposal can only be accepted until then
+    ProposeMinter {
+        new_minter: String,
+        expires: Option<Expiration>,
+    },
+    /// Accept a pending minter proposal, can only be called by the proposed minter
+    AcceptMinter {},
+    /// Withdraw a pending minter proposal, can only be called by the contract minter
+    CancelMinterProposal {},
+
+    /// Halt minting, token movements or both, can only be called by a pauser.
+    /// Pausing again widens the scope, queries keep working throughout
+    Pause { scope: PauseScope },
+    /// Lift any pause, can only be called by a pauser
+    Unpause {},
+
+    /// Give an address a role, can only be called by an admin
+    GrantRole { role: Role, address: String },
+    /// Take a role away from an address, can only be called by an admin
+    RevokeRole { role: Role, address: String },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct MintMsg {
-    /// Unique ID of the NFT
-    pub token_id: TokenId,
+    /// Unique ID of the NFT. If unset, the contract assigns the next ID after the
+    /// highest one ever used.
+    pub token_id: Option<TokenId>,
     /// The owner of the newly minted NFT
     pub owner: String,
     /// Universal resource identifier for this NFT
@@ -60,4 +133,44 @@ pub struct MintMsg {
     pub token_uri: Option<String>,
     /// Any custom extension used by this contract
     pub extension: Metadata,
+    /// Overrides the collection's default royalty for this token
+    pub royalty: Option<Royalty>,
+    /// Overrides whether this token can be transferred, soulbound tokens can only be burned
+    /// by their owner
+    pub transferable: Option<bool>,
+}
+
+/// Royalty owed to `payment_address` on every sale, as a share of the sale price
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Royalty {
+    pub payment_address: String,
+    /// Must be between 0 and 1 (100%)
+    pub share: Decimal,
+}
+
+impl Royalty {
+    /// Checks the address and share, returning the form kept in storage
+    pub(crate) fn validate(&self, api: &dyn Api) -> Result<state::Royalty, ContractError> {
+        if self.share > Decimal::one() {
+            return Err(ContractError::InvalidRoyaltyShare { share: self.share });
+        }
+        Ok(state::Royalty {
+            payment_address: api.addr_validate(&self.payment_address)?,
+            share: self.share,
+        })
+    }
+}
+
+/// Returned in `Response::data` after a successful `Mint`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MintResponse {
+    /// The ID of the minted token, including when it was assigned by the contract
+    pub token_id: TokenId,
+}
+
+/// Returned in `Response::data` after a successful `BatchMint`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BatchMintResponse {
+    /// The IDs of the minted tokens, in the order they were given
+    pub token_ids: Vec<TokenId>,
 }
@@ -1,7 +1,9 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+use crate::{MetadataLimits, Royalty, TokenId, TraitSchema};
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     /// Name of the NFT contract
     pub name: String,
@@ -12,10 +14,47 @@ pub struct InstantiateMsg {
     /// This is designed for a base NFT that is controlled by an external program
     /// or contract. You will likely replace this with custom logic in custom NFTs
     pub minter: String,
+    /// Holder of the admin role, who can grant further roles. Defaults to the minter
+    pub admin: Option<String>,
+
+    /// Maximum number of entries accepted by batch messages such as `BatchMint`.
+    /// Defaults to 50 if unset.
+    pub max_batch_size: Option<u32>,
+    /// Maximum number of approvals a single token can hold at once.
+    /// Defaults to 10 if unset.
+    pub max_approvals: Option<u32>,
+    /// Bounds on the metadata of each token, checked at mint and on metadata updates.
+    /// Defaults to 2048 bytes per field, 16384 bytes in total and 50 attributes if unset.
+    pub metadata_limits: Option<MetadataLimits>,
+
+    /// Maximum number of tokens that can ever be minted, burned tokens included.
+    /// Unlimited if unset.
+    pub max_supply: Option<u64>,
+    /// Highest token ID that can be minted. Unlimited if unset.
+    pub max_token_id: Option<TokenId>,
+    /// Prefix of the URI of tokens minted without one, followed by the token ID
+    pub base_uri: Option<String>,
+    /// Appended after the token ID to URIs derived from the base URI
+    pub token_uri_suffix: Option<String>,
+    /// Royalty for every token that does not set its own
+    pub royalty: Option<Royalty>,
+    /// Whether tokens can be transferred unless they say otherwise at mint.
+    /// Defaults to true; set to false for soulbound collections
+    pub transferable: Option<bool>,
+    /// Attributes every token must conform to, checked at mint and on metadata updates.
+    /// Any attributes are accepted if unset
+    pub trait_schema: Option<TraitSchema>,
 }
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
 pub struct MigrateMsg {
     pub name: Option<String>,
     pub symbol: Option<String>,
     pub minter: Option<String>,
+    pub max_batch_size: Option<u32>,
+    /// Tokens already holding more approvals keep them until they are transferred
+    pub max_approvals: Option<u32>,
+    /// Can only lower the current cap, never raise or remove it
+    pub max_supply: Option<u64>,
+    /// Can only lower the current cap, never raise or remove it
+    pub max_token_id: Option<TokenId>,
 }
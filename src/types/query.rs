@@ -1,9 +1,10 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use cosmwasm_std::{Decimal, Order, Uint128};
 use cw0::Expiration;
 
-use crate::{Metadata, TokenId};
+use crate::{Metadata, MetadataLimits, Role, TokenId, TraitSchema};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -15,6 +16,23 @@ pub enum QueryMsg {
         /// unset or false will filter out expired approvals, you must set to true to see them
         include_expired: Option<bool>,
     },
+    /// Return the approval of the spender on the given token, error if there is none.
+    /// The owner is reported as approved with no expiration
+    /// Return type: `ApprovalResponse`
+    Approval {
+        token_id: TokenId,
+        spender: String,
+        include_expired: Option<bool>,
+    },
+    /// Return all approvals on the given token, error if token does not exist
+    /// Return type: `ApprovalsResponse`
+    Approvals {
+        token_id: TokenId,
+        include_expired: Option<bool>,
+    },
+    /// Return whether the sender could transfer or send the given token right now, and why
+    /// Return type: `CanTransferResponse`
+    CanTransfer { token_id: TokenId, sender: String },
     /// List all operators that can access all of the owner's tokens
     /// Return type: `ApprovedForAllResponse`
     ApprovedForAll {
@@ -23,9 +41,34 @@ pub enum QueryMsg {
         include_expired: Option<bool>,
         start_after: Option<String>,
         limit: Option<u32>,
+        /// Operator to stop at
+        end: Option<String>,
+        /// unset or false excludes `start_after` and `end` themselves
+        inclusive: Option<bool>,
+        /// Ascending if unset
+        order: Option<OrderBy>,
+    },
+    /// List all owners that made the operator an operator of all their tokens
+    /// Return type: `OperatorGrantsResponse`
+    OperatorGrants {
+        operator: String,
+        /// unset or false will filter out expired items, you must set to true to see them
+        include_expired: Option<bool>,
+        start_after: Option<String>,
+        limit: Option<u32>,
     },
     /// Total number of tokens issued
     NumTokens {},
+    /// Number of tokens held by the owner
+    /// Return type: `BalanceResponse`
+    Balance { owner: String },
+    /// List owners by the number of tokens they hold, largest first.
+    /// `start_after` is the (balance, owner) pair of the last holder on the previous page.
+    /// Return type: `TopHoldersResponse`
+    TopHolders {
+        start_after: Option<(u64, String)>,
+        limit: Option<u32>,
+    },
 
     /// With MetaData Extension.
     /// Returns top-level metadata about the contract: `ContractInfoResponse`
@@ -46,24 +89,125 @@ pub enum QueryMsg {
     /// With Enumerable extension.
     /// Returns all tokens owned by the given address, [] if unset.
     /// Return type: TokensResponse.
+    /// With descending order, `start_after` is where iteration starts from the top.
     Tokens {
         owner: String,
         start_after: Option<TokenId>,
         limit: Option<u32>,
+        /// Token ID to stop at
+        end: Option<TokenId>,
+        /// unset or false excludes `start_after` and `end` themselves
+        inclusive: Option<bool>,
+        /// Ascending if unset
+        order: Option<OrderBy>,
     },
     /// With Enumerable extension.
     /// Requires pagination. Lists all token_ids controlled by the contract.
+    /// Takes the same range arguments as `Tokens`.
     /// Return type: TokensResponse.
     AllTokens {
         start_after: Option<TokenId>,
         limit: Option<u32>,
+        end: Option<TokenId>,
+        inclusive: Option<bool>,
+        order: Option<OrderBy>,
     },
 
+    /// Lists the tokens with the given attribute, ordered by token ID
+    /// Return type: TokensResponse
+    TokensByTrait {
+        trait_type: String,
+        value: String,
+        start_after: Option<TokenId>,
+        limit: Option<u32>,
+    },
+    /// Lists the distinct values of a trait type, with the number of tokens carrying each
+    /// Return type: TraitValuesResponse
+    TraitValues {
+        trait_type: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Lists the values of a trait type with how many and which share of all tokens carry them
+    /// Return type: TraitStatsResponse
+    TraitStats {
+        trait_type: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Return how common each of the token's traits is, and its rarity score
+    /// Return type: TokenRarityResponse
+    TokenRarity { token_id: TokenId },
+
+    /// Return the attributes tokens must conform to, if any
+    /// Return type: TraitSchemaResponse
+    TraitSchema {},
+
     /// Return the minter
     Minter {},
+    /// Return the proposed next minter, if any
+    /// Return type: PendingMinterResponse
+    PendingMinter {},
+    /// List the addresses holding the given role
+    /// Return type: RoleHoldersResponse
+    RoleHolders {
+        role: Role,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 
     /// Return the highest used token ID
     HighestTokenId {},
+
+    /// Return whether metadata is frozen for the whole collection and, if given, for one token
+    /// Return type: FreezeStatusResponse
+    FreezeStatus { token_id: Option<TokenId> },
+
+    /// With CW-2981 royalty extension.
+    /// Returns who should be paid how much when the token is sold for `sale_price`
+    /// Return type: RoyaltiesInfoResponse
+    RoyaltyInfo {
+        token_id: TokenId,
+        sale_price: Uint128,
+    },
+    /// With CW-2981 royalty extension.
+    /// Lets marketplaces detect that this contract implements royalties
+    /// Return type: CheckRoyaltiesResponse
+    CheckRoyalties {},
+
+    /// Return which operations are currently paused
+    /// Return type: PauseStatusResponse
+    PauseStatus {},
+
+    /// Return the maximum number of approvals a single token can hold
+    /// Return type: ApprovalLimitResponse
+    ApprovalLimit {},
+
+    /// Return the bounds on the metadata of each token
+    /// Return type: MetadataLimitsResponse
+    MetadataLimits {},
+
+    /// Return the supply caps and how much of them has been used
+    /// Return type: SupplyInfoResponse
+    SupplyInfo {},
+}
+
+/// Order in which paginated queries return their items
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderBy {
+    Ascending,
+    Descending,
+}
+
+impl From<OrderBy> for Order {
+    fn from(order: OrderBy) -> Order {
+        match order {
+            OrderBy::Ascending => Order::Ascending,
+            OrderBy::Descending => Order::Descending,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -82,16 +226,128 @@ pub struct Approval {
     pub expires: Expiration,
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ApprovalResponse {
+    pub approval: Approval,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ApprovalsResponse {
+    pub approvals: Vec<Approval>,
+}
+
+/// Why an address may move a token
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferAuthority {
+    /// The address owns the token
+    Owner,
+    /// The address holds an approval on the token
+    TokenApproval,
+    /// The owner made the address an operator of all their tokens
+    Operator,
+}
+
+/// Why a token cannot move even for an address allowed to move it
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferBlock {
+    /// Transfers are paused
+    Paused,
+    /// The token is soulbound
+    NonTransferable,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct CanTransferResponse {
+    pub can_transfer: bool,
+    /// Set if the address is allowed to move the token, even while it is blocked
+    pub authority: Option<TransferAuthority>,
+    /// Set if no address can move the token right now
+    pub blocked_by: Option<TransferBlock>,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct ApprovedForAllResponse {
     pub operators: Vec<Approval>,
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct OperatorGrant {
+    /// Account whose tokens the operator can transfer/send
+    pub owner: String,
+    /// When the grant expires (maybe Expiration::never)
+    pub expires: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct OperatorGrantsResponse {
+    pub grants: Vec<OperatorGrant>,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct NumTokensResponse {
     pub count: u64,
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BalanceResponse {
+    pub balance: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct Holder {
+    pub owner: String,
+    pub balance: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TopHoldersResponse {
+    pub holders: Vec<Holder>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TraitValue {
+    pub value: String,
+    /// Number of tokens with this value
+    pub count: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TraitValuesResponse {
+    pub values: Vec<TraitValue>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TraitStat {
+    pub trait_type: String,
+    pub value: String,
+    /// Number of tokens with this value
+    pub count: u64,
+    /// Share of all existing tokens with this value
+    pub frequency: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TraitStatsResponse {
+    /// Number of existing tokens the frequencies are relative to
+    pub total_tokens: u64,
+    pub values: Vec<TraitStat>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TokenRarityResponse {
+    /// One entry per distinct trait of the token
+    pub traits: Vec<TraitStat>,
+    /// Sum of the inverse frequencies of the token's traits, higher is rarer
+    pub rarity_score: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TraitSchemaResponse {
+    pub schema: Option<TraitSchema>,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct HighestTokenIdResponse {
     pub highest_token_id: Option<TokenId>,
@@ -101,6 +357,73 @@ pub struct HighestTokenIdResponse {
 pub struct ContractInfoResponse {
     pub name: String,
     pub symbol: String,
+    /// Maximum number of tokens that can ever be minted, burned tokens included
+    pub max_supply: Option<u64>,
+    /// Highest token ID that can be minted
+    pub max_token_id: Option<TokenId>,
+    /// Prefix of the URI of tokens minted without one, followed by the token ID
+    pub base_uri: Option<String>,
+    /// Appended after the token ID to URIs derived from the base URI, such as `.json`
+    pub token_uri_suffix: Option<String>,
+}
+
+impl ContractInfoResponse {
+    /// The URI of a token, derived from the base URI unless the token has its own
+    pub fn token_uri(&self, token_id: TokenId, token_uri: Option<String>) -> Option<String> {
+        token_uri.or_else(|| {
+            self.base_uri.as_ref().map(|base_uri| {
+                let suffix = self.token_uri_suffix.as_deref().unwrap_or_default();
+                format!("{}{}{}", base_uri, token_id, suffix)
+            })
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct RoyaltiesInfoResponse {
+    /// Where to send the royalty, empty if none is owed
+    pub address: String,
+    pub royalty_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct CheckRoyaltiesResponse {
+    pub royalty_payments: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ApprovalLimitResponse {
+    pub max_approvals: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct MetadataLimitsResponse {
+    pub limits: MetadataLimits,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PauseStatusResponse {
+    pub mint_paused: bool,
+    /// Covers transfers, sends and burns
+    pub transfer_paused: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct FreezeStatusResponse {
+    /// True once the metadata of every token is frozen
+    pub all_frozen: bool,
+    /// Whether the requested token's metadata is frozen, unset if no token was requested
+    pub token_frozen: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SupplyInfoResponse {
+    pub max_supply: Option<u64>,
+    pub max_token_id: Option<TokenId>,
+    /// Number of tokens ever minted, including those since burned
+    pub minted: u64,
+    /// How many more tokens can be minted, unset if there is no cap
+    pub remaining: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -111,6 +434,8 @@ pub struct NftInfoResponse {
     pub token_uri: Option<String>,
     /// You can add any custom metadata here when you extend cw721-base
     pub extension: Metadata,
+    /// False for soulbound tokens, which cannot be transferred, sent or approved
+    pub transferable: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -123,8 +448,8 @@ pub struct AllNftInfoResponse {
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct TokensResponse {
-    /// Contains all token_ids in lexicographical ordering
-    /// If there are more than `limit`, use `start_from` in future queries
+    /// Contains token_ids in the requested order
+    /// If there are more than `limit`, use `start_after` in future queries
     /// to achieve pagination.
     pub tokens: Vec<TokenId>,
 }
@@ -134,3 +459,17 @@ pub struct TokensResponse {
 pub struct MinterResponse {
     pub minter: String,
 }
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct RoleHoldersResponse {
+    pub holders: Vec<String>,
+}
+
+/// Shows who has been proposed as the next minter
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PendingMinterResponse {
+    /// Unset if there is no open proposal
+    pub pending_minter: Option<String>,
+    /// When the proposal expires (maybe Expiration::never)
+    pub expires: Option<Expiration>,
+}
@@ -36,10 +36,42 @@ impl ReceiveMsg {
     }
 }
 
-/// This is just a helper to properly serialize the above message.
+/// BatchReceiveMsg should be de/serialized under `BatchReceive()` variant in a ExecuteMsg
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct BatchReceiveMsg {
+    pub sender: String,
+    pub token_ids: Vec<TokenId>,
+    pub msg: Binary,
+}
+
+impl BatchReceiveMsg {
+    /// serializes the message
+    pub fn into_binary(self) -> StdResult<Binary> {
+        let msg = ReceiverExecuteMsg::BatchReceiveNft(self);
+        to_binary(&msg)
+    }
+
+    /// creates a cosmos_msg sending this struct to the named contract
+    pub fn into_cosmos_msg<T: Into<String>, C>(self, contract_addr: T) -> StdResult<CosmosMsg<C>>
+    where
+        C: Clone + std::fmt::Debug + PartialEq + JsonSchema,
+    {
+        let msg = self.into_binary()?;
+        let execute = WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg,
+            funds: vec![],
+        };
+        Ok(execute.into())
+    }
+}
+
+/// This is just a helper to properly serialize the above messages.
 /// The actual receiver should include this variant in the larger ExecuteMsg enum
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 enum ReceiverExecuteMsg {
     ReceiveNft(ReceiveMsg),
+    BatchReceiveNft(BatchReceiveMsg),
 }
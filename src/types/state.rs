@@ -1,47 +1,154 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, BlockInfo, StdResult, Storage};
+use cosmwasm_std::{to_vec, Addr, BlockInfo, Decimal, Order, StdError, StdResult, Storage};
 
-use crate::{ContractInfoResponse, Expiration, TokenId};
-use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+use crate::{ContractError, ContractInfoResponse, Expiration, TokenId};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex, U64Key};
 
 pub struct DummyNftContract<'a> {
     pub contract_info: Item<'a, ContractInfoResponse>,
     pub minter: Item<'a, Addr>,
+    /// Set while a minter handoff is waiting to be accepted
+    pub pending_minter: Item<'a, PendingMinter>,
     pub token_count: Item<'a, u64>,
+    /// Number of tokens ever minted, including those since burned
+    pub minted_count: Item<'a, u64>,
     pub highest_token_id: Item<'a, TokenId>,
     /// Stored as (granter, operator) giving operator full control over granter's account
     pub operators: Map<'a, (&'a Addr, &'a Addr), Expiration>,
+    /// The same grants as `operators`, stored as (operator, granter)
+    pub operator_grants: Map<'a, (&'a Addr, &'a Addr), Expiration>,
     pub tokens: IndexedMap<'a, TokenId, TokenInfo, TokenIndexes<'a>>,
     pub burned: Map<'a, TokenId, ()>,
+    /// Attributes minted tokens must conform to, any are accepted if unset
+    pub trait_schema: Item<'a, TraitSchema>,
+    /// Tokens carrying each trait, stored as (trait_type, value, token)
+    pub traits: Map<'a, (&'a str, &'a str, TokenId), ()>,
+    /// Number of tokens carrying each trait, stored as (trait_type, value)
+    pub trait_counts: Map<'a, (&'a str, &'a str), u64>,
+    /// Number of tokens held by each owner, owners without tokens are not stored
+    pub balances: Map<'a, &'a Addr, u64>,
+    /// The same balances, stored as (balance, owner) so they can be ranked
+    pub holders: Map<'a, (U64Key, &'a Addr), ()>,
+    /// Sends waiting for the receiver's reply so they can be undone on failure
+    pub pending_sends: Map<'a, TokenId, PendingSend>,
+    /// Royalty applied to tokens without their own override
+    pub default_royalty: Item<'a, Royalty>,
+    pub token_royalties: Map<'a, TokenId, Royalty>,
+    /// Whether tokens are transferable unless they say otherwise
+    pub transferable: Item<'a, bool>,
+    /// Tokens that can never change hands
+    pub soulbound: Map<'a, TokenId, ()>,
+    /// Set while some operations are halted
+    pub paused: Item<'a, PauseScope>,
+    /// Tokens whose metadata can no longer change
+    pub frozen_tokens: Map<'a, TokenId, ()>,
+    /// Set once the metadata of the whole collection can no longer change
+    pub all_metadata_frozen: Item<'a, bool>,
+    /// Number of frozen tokens without a URI of their own, which derive it from the base URI
+    pub frozen_base_uri_tokens: Item<'a, u64>,
+    pub max_batch_size: Item<'a, u32>,
+    pub max_approvals: Item<'a, u32>,
+    pub metadata_limits: Item<'a, MetadataLimits>,
+    /// Layout of token ID storage keys, unset for the little-endian layout of older versions
+    pub token_key_version: Item<'a, u8>,
+    /// Version of `balances` and `holders`, unset for older versions that did not track balances
+    pub balance_index_version: Item<'a, u8>,
+    /// Version of the trait index, unset for older versions that did not index traits
+    pub trait_index_version: Item<'a, u8>,
+    /// Version of `operator_grants`, unset for older versions that only kept `operators`
+    pub operator_index_version: Item<'a, u8>,
+    /// Stored as (role, holder)
+    pub roles: Map<'a, (&'a str, &'a Addr), ()>,
 }
 
 impl Default for DummyNftContract<'static> {
     fn default() -> Self {
-        let indexes = TokenIndexes {
-            owner: MultiIndex::new(token_owner_idx, TOKENS_KEY, TOKENS_OWNER_KEY),
-        };
         Self {
             contract_info: Item::new(CONTRACT_KEY),
             minter: Item::new(MINTER_KEY),
+            pending_minter: Item::new(PENDING_MINTER_KEY),
             token_count: Item::new(TOKEN_COUNT_KEY),
+            minted_count: Item::new(MINTED_COUNT_KEY),
             highest_token_id: Item::new(HIGHEST_TOKEN_ID_KEY),
             operators: Map::new(OPERATOR_KEY),
-            tokens: IndexedMap::new(TOKENS_KEY, indexes),
+            operator_grants: Map::new(OPERATOR_GRANTS_KEY),
+            tokens: IndexedMap::new(TOKENS_KEY, token_indexes()),
             burned: Map::new(BURNED_KEY),
+            trait_schema: Item::new(TRAIT_SCHEMA_KEY),
+            traits: Map::new(TRAITS_KEY),
+            trait_counts: Map::new(TRAIT_COUNTS_KEY),
+            balances: Map::new(BALANCES_KEY),
+            holders: Map::new(HOLDERS_KEY),
+            pending_sends: Map::new(PENDING_SENDS_KEY),
+            default_royalty: Item::new(DEFAULT_ROYALTY_KEY),
+            token_royalties: Map::new(TOKEN_ROYALTIES_KEY),
+            transferable: Item::new(TRANSFERABLE_KEY),
+            soulbound: Map::new(SOULBOUND_KEY),
+            paused: Item::new(PAUSED_KEY),
+            frozen_tokens: Map::new(FROZEN_TOKENS_KEY),
+            all_metadata_frozen: Item::new(ALL_METADATA_FROZEN_KEY),
+            frozen_base_uri_tokens: Item::new(FROZEN_BASE_URI_TOKENS_KEY),
+            max_batch_size: Item::new(MAX_BATCH_SIZE_KEY),
+            max_approvals: Item::new(MAX_APPROVALS_KEY),
+            metadata_limits: Item::new(METADATA_LIMITS_KEY),
+            token_key_version: Item::new(TOKEN_KEY_VERSION_KEY),
+            balance_index_version: Item::new(BALANCE_INDEX_VERSION_KEY),
+            trait_index_version: Item::new(TRAIT_INDEX_VERSION_KEY),
+            operator_index_version: Item::new(OPERATOR_INDEX_VERSION_KEY),
+            roles: Map::new(ROLES_KEY),
         }
     }
 }
 
 const CONTRACT_KEY: &str = "nft_info";
 const MINTER_KEY: &str = "minter";
+const PENDING_MINTER_KEY: &str = "pending_minter";
+const PENDING_SENDS_KEY: &str = "pending_sends";
 const TOKEN_COUNT_KEY: &str = "num_tokens";
+const MINTED_COUNT_KEY: &str = "num_minted";
 const HIGHEST_TOKEN_ID_KEY: &str = "highest_token_id";
 const OPERATOR_KEY: &str = "operators";
+const OPERATOR_GRANTS_KEY: &str = "operator_grants";
 const TOKENS_KEY: &str = "tokens";
 const TOKENS_OWNER_KEY: &str = "tokens__owner";
 const BURNED_KEY: &str = "burned";
+const TRAIT_SCHEMA_KEY: &str = "trait_schema";
+const TRAITS_KEY: &str = "traits";
+const TRAIT_COUNTS_KEY: &str = "trait_counts";
+const BALANCES_KEY: &str = "balances";
+const HOLDERS_KEY: &str = "holders";
+const DEFAULT_ROYALTY_KEY: &str = "default_royalty";
+const TOKEN_ROYALTIES_KEY: &str = "token_royalties";
+const TRANSFERABLE_KEY: &str = "transferable";
+const SOULBOUND_KEY: &str = "soulbound";
+const PAUSED_KEY: &str = "paused";
+const FROZEN_TOKENS_KEY: &str = "frozen_tokens";
+const ALL_METADATA_FROZEN_KEY: &str = "all_metadata_frozen";
+const FROZEN_BASE_URI_TOKENS_KEY: &str = "frozen_base_uri_tokens";
+const MAX_BATCH_SIZE_KEY: &str = "max_batch_size";
+const MAX_APPROVALS_KEY: &str = "max_approvals";
+const METADATA_LIMITS_KEY: &str = "metadata_limits";
+const TOKEN_KEY_VERSION_KEY: &str = "token_key_version";
+const BALANCE_INDEX_VERSION_KEY: &str = "balance_index_version";
+const TRAIT_INDEX_VERSION_KEY: &str = "trait_index_version";
+const OPERATOR_INDEX_VERSION_KEY: &str = "operator_index_version";
+const ROLES_KEY: &str = "roles";
+
+/// Token IDs are stored big-endian, see `TokenId::to_bytes`
+pub const TOKEN_KEY_VERSION: u8 = 1;
+/// Tokens are counted per owner, see `increase_balance`
+pub const BALANCE_INDEX_VERSION: u8 = 1;
+/// Traits of every token are indexed, see `index_traits`
+pub const TRAIT_INDEX_VERSION: u8 = 1;
+/// Operators are indexed by operator in `operator_grants`
+pub const OPERATOR_INDEX_VERSION: u8 = 1;
+
+/// Used when no maximum batch size was configured
+pub const DEFAULT_MAX_BATCH_SIZE: u32 = 50;
+/// Used when no maximum number of approvals per token was configured
+pub const DEFAULT_MAX_APPROVALS: u32 = 10;
 
 impl<'a> DummyNftContract<'a> {
     pub fn token_count(&self, storage: &dyn Storage) -> StdResult<u64> {
@@ -49,17 +156,296 @@ impl<'a> DummyNftContract<'a> {
     }
 
     pub fn increment_tokens(&self, storage: &mut dyn Storage) -> StdResult<u64> {
-        let val = self.token_count(storage)? + 1;
+        self.add_tokens(storage, 1)
+    }
+
+    /// Records newly minted tokens in both the live and the all-time counts
+    pub fn add_tokens(&self, storage: &mut dyn Storage, amount: u64) -> StdResult<u64> {
+        let minted = self.minted_count(storage)? + amount;
+        self.minted_count.save(storage, &minted)?;
+        let val = self.token_count(storage)? + amount;
         self.token_count.save(storage, &val)?;
         Ok(val)
     }
 
+    pub fn minted_count(&self, storage: &dyn Storage) -> StdResult<u64> {
+        Ok(self.minted_count.may_load(storage)?.unwrap_or_default())
+    }
+
     pub fn decrement_tokens(&self, storage: &mut dyn Storage) -> StdResult<u64> {
         let val = self.token_count(storage)? - 1;
         self.token_count.save(storage, &val)?;
         Ok(val)
     }
 
+    /// Rejects metadata exceeding the configured limits or holding malformed fields
+    pub fn check_metadata(
+        &self,
+        storage: &dyn Storage,
+        metadata: &Metadata,
+    ) -> Result<(), ContractError> {
+        metadata.validate(&self.metadata_limits(storage)?)?;
+        self.check_traits(storage, metadata)
+    }
+
+    /// Rejects attributes that do not conform to the trait schema, if there is one
+    pub fn check_traits(
+        &self,
+        storage: &dyn Storage,
+        metadata: &Metadata,
+    ) -> Result<(), ContractError> {
+        match self.trait_schema.may_load(storage)? {
+            Some(schema) => schema.validate(&metadata.attributes),
+            None => Ok(()),
+        }
+    }
+
+    /// Adds the token's attributes to the trait index, listing a token once per trait
+    pub fn index_traits(
+        &self,
+        storage: &mut dyn Storage,
+        token_id: TokenId,
+        metadata: &Metadata,
+    ) -> StdResult<()> {
+        for attr in &metadata.attributes {
+            let (trait_type, value) = (attr.trait_type.as_str(), attr.value.as_str());
+            if self.traits.has(storage, (trait_type, value, token_id)) {
+                continue;
+            }
+            self.traits
+                .save(storage, (trait_type, value, token_id), &())?;
+            let count = self
+                .trait_counts
+                .may_load(storage, (trait_type, value))?
+                .unwrap_or_default();
+            self.trait_counts
+                .save(storage, (trait_type, value), &(count + 1))?;
+        }
+        Ok(())
+    }
+
+    /// Removes the token's attributes from the trait index
+    pub fn unindex_traits(
+        &self,
+        storage: &mut dyn Storage,
+        token_id: TokenId,
+        metadata: &Metadata,
+    ) -> StdResult<()> {
+        for attr in &metadata.attributes {
+            let (trait_type, value) = (attr.trait_type.as_str(), attr.value.as_str());
+            if !self.traits.has(storage, (trait_type, value, token_id)) {
+                continue;
+            }
+            self.traits.remove(storage, (trait_type, value, token_id));
+            let count = self.trait_counts.load(storage, (trait_type, value))? - 1;
+            if count == 0 {
+                self.trait_counts.remove(storage, (trait_type, value));
+            } else {
+                self.trait_counts
+                    .save(storage, (trait_type, value), &count)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn balance(&self, storage: &dyn Storage, owner: &Addr) -> StdResult<u64> {
+        Ok(self.balances.may_load(storage, owner)?.unwrap_or_default())
+    }
+
+    pub fn increase_balance(&self, storage: &mut dyn Storage, owner: &Addr) -> StdResult<u64> {
+        let old = self.balance(storage, owner)?;
+        self.set_balance(storage, owner, old, old + 1)?;
+        Ok(old + 1)
+    }
+
+    pub fn decrease_balance(&self, storage: &mut dyn Storage, owner: &Addr) -> StdResult<u64> {
+        let old = self.balance(storage, owner)?;
+        self.set_balance(storage, owner, old, old - 1)?;
+        Ok(old - 1)
+    }
+
+    /// Updates both the balance and the ranking of an owner
+    fn set_balance(
+        &self,
+        storage: &mut dyn Storage,
+        owner: &Addr,
+        old: u64,
+        new: u64,
+    ) -> StdResult<()> {
+        if old > 0 {
+            self.holders.remove(storage, (U64Key::new(old), owner));
+        }
+        if new > 0 {
+            self.balances.save(storage, owner, &new)?;
+            self.holders.save(storage, (U64Key::new(new), owner), &())
+        } else {
+            self.balances.remove(storage, owner);
+            Ok(())
+        }
+    }
+
+    pub fn max_batch_size(&self, storage: &dyn Storage) -> StdResult<u32> {
+        Ok(self
+            .max_batch_size
+            .may_load(storage)?
+            .unwrap_or(DEFAULT_MAX_BATCH_SIZE))
+    }
+
+    /// Rewrites the token and burned token keys from the layout of older versions to the current
+    /// one, no other map keyed by token ID existed back then. Does nothing if the keys are already current. Loads all tokens at once, so very large
+    /// collections may need more gas than a single migration allows
+    pub fn migrate_token_keys(&self, storage: &mut dyn Storage) -> StdResult<()> {
+        if self
+            .token_key_version
+            .may_load(storage)?
+            .unwrap_or_default()
+            >= TOKEN_KEY_VERSION
+        {
+            return Ok(());
+        }
+
+        // read everything before writing anything, old and new keys share a namespace
+        let legacy_tokens: IndexedMap<Vec<u8>, TokenInfo, TokenIndexes> =
+            IndexedMap::new(TOKENS_KEY, token_indexes());
+        let tokens: Vec<_> = legacy_tokens
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<_>>()?;
+        for (key, _) in &tokens {
+            legacy_tokens.remove(storage, key.clone())?;
+        }
+        for (key, token) in tokens {
+            self.tokens
+                .save(storage, TokenId::from_legacy_bytes(&key)?, &token)?;
+        }
+
+        let legacy_burned: Map<Vec<u8>, ()> = Map::new(BURNED_KEY);
+        let burned: Vec<_> = legacy_burned
+            .keys(storage, None, None, Order::Ascending)
+            .collect();
+        for key in &burned {
+            legacy_burned.remove(storage, key.clone());
+        }
+        for key in burned {
+            self.burned
+                .save(storage, TokenId::from_legacy_bytes(&key)?, &())?;
+        }
+
+        self.token_key_version.save(storage, &TOKEN_KEY_VERSION)
+    }
+
+    pub fn max_approvals(&self, storage: &dyn Storage) -> StdResult<u32> {
+        Ok(self
+            .max_approvals
+            .may_load(storage)?
+            .unwrap_or(DEFAULT_MAX_APPROVALS))
+    }
+
+    pub fn metadata_limits(&self, storage: &dyn Storage) -> StdResult<MetadataLimits> {
+        Ok(self.metadata_limits.may_load(storage)?.unwrap_or_default())
+    }
+
+    pub fn has_role(&self, storage: &dyn Storage, role: Role, addr: &Addr) -> bool {
+        self.roles.has(storage, (role.as_str(), addr))
+    }
+
+    pub fn add_role(&self, storage: &mut dyn Storage, role: Role, addr: &Addr) -> StdResult<()> {
+        self.roles.save(storage, (role.as_str(), addr), &())
+    }
+
+    pub fn remove_role(&self, storage: &mut dyn Storage, role: Role, addr: &Addr) {
+        self.roles.remove(storage, (role.as_str(), addr))
+    }
+
+    /// Gives an operator control over all of the granter's tokens, keeping both directions in sync
+    pub fn set_operator(
+        &self,
+        storage: &mut dyn Storage,
+        granter: &Addr,
+        operator: &Addr,
+        expires: &Expiration,
+    ) -> StdResult<()> {
+        self.operators.save(storage, (granter, operator), expires)?;
+        self.operator_grants
+            .save(storage, (operator, granter), expires)
+    }
+
+    pub fn remove_operator(&self, storage: &mut dyn Storage, granter: &Addr, operator: &Addr) {
+        self.operators.remove(storage, (granter, operator));
+        self.operator_grants.remove(storage, (operator, granter));
+    }
+
+    /// Replaces the primary minter, moving the minter role along with it
+    pub fn set_primary_minter(&self, storage: &mut dyn Storage, minter: &Addr) -> StdResult<()> {
+        if let Some(old) = self.minter.may_load(storage)? {
+            self.remove_role(storage, Role::Minter, &old);
+        }
+        self.minter.save(storage, minter)?;
+        self.add_role(storage, Role::Minter, minter)
+    }
+
+    pub fn collection_transferable(&self, storage: &dyn Storage) -> StdResult<bool> {
+        Ok(self.transferable.may_load(storage)?.unwrap_or(true))
+    }
+
+    pub fn is_transferable(&self, storage: &dyn Storage, token_id: TokenId) -> bool {
+        !self.soulbound.has(storage, token_id)
+    }
+
+    pub fn is_paused(&self, storage: &dyn Storage, scope: PauseScope) -> StdResult<bool> {
+        Ok(self
+            .paused
+            .may_load(storage)?
+            .is_some_and(|paused| paused.covers(scope)))
+    }
+
+    pub fn frozen_base_uri_tokens(&self, storage: &dyn Storage) -> StdResult<u64> {
+        Ok(self
+            .frozen_base_uri_tokens
+            .may_load(storage)?
+            .unwrap_or_default())
+    }
+
+    /// Freezes the token's metadata, counting it if it relies on the base URI
+    pub fn freeze_token(
+        &self,
+        storage: &mut dyn Storage,
+        token: &TokenInfo,
+        token_id: TokenId,
+    ) -> StdResult<()> {
+        if token.token_uri.is_none() && !self.frozen_tokens.has(storage, token_id) {
+            let count = self.frozen_base_uri_tokens(storage)?;
+            self.frozen_base_uri_tokens.save(storage, &(count + 1))?;
+        }
+        self.frozen_tokens.save(storage, token_id, &())
+    }
+
+    /// Drops the freeze flag of a burned token, also removing it from the count
+    pub fn unfreeze_burned(
+        &self,
+        storage: &mut dyn Storage,
+        token: &TokenInfo,
+        token_id: TokenId,
+    ) -> StdResult<()> {
+        if token.token_uri.is_none() && self.frozen_tokens.has(storage, token_id) {
+            let count = self.frozen_base_uri_tokens(storage)?;
+            self.frozen_base_uri_tokens
+                .save(storage, &count.saturating_sub(1))?;
+        }
+        self.frozen_tokens.remove(storage, token_id);
+        Ok(())
+    }
+
+    pub fn all_metadata_frozen(&self, storage: &dyn Storage) -> StdResult<bool> {
+        Ok(self
+            .all_metadata_frozen
+            .may_load(storage)?
+            .unwrap_or_default())
+    }
+
+    pub fn is_metadata_frozen(&self, storage: &dyn Storage, token_id: TokenId) -> StdResult<bool> {
+        Ok(self.all_metadata_frozen(storage)? || self.frozen_tokens.has(storage, token_id))
+    }
+
     pub fn update_highest(&self, storage: &mut dyn Storage, token_id: TokenId) -> StdResult<()> {
         let new_highest = match self.highest_token_id.may_load(storage)? {
             Some(old_highest) => old_highest.max(token_id),
@@ -85,6 +471,84 @@ pub struct TokenInfo {
     pub extension: Metadata,
 }
 
+/// Which operations a pause applies to
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PauseScope {
+    /// Minting new tokens
+    Mint,
+    /// Moving tokens: transfers, sends and burns
+    Transfer,
+    /// Both of the above
+    All,
+}
+
+impl PauseScope {
+    pub fn covers(&self, other: PauseScope) -> bool {
+        *self == PauseScope::All || *self == other
+    }
+
+    /// The scope covering both `self` and `other`
+    pub fn merge(self, other: PauseScope) -> PauseScope {
+        if self.covers(other) {
+            self
+        } else if other.covers(self) {
+            other
+        } else {
+            PauseScope::All
+        }
+    }
+}
+
+/// Permissions that can be granted to any number of addresses
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// Can grant and revoke roles
+    Admin,
+    /// Can mint new tokens
+    Minter,
+    /// Can change the metadata of existing tokens
+    MetadataEditor,
+    /// Can pause and unpause the contract
+    Pauser,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Admin => "admin",
+            Role::Minter => "minter",
+            Role::MetadataEditor => "metadata_editor",
+            Role::Pauser => "pauser",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Royalty {
+    /// Account that receives the royalty
+    pub payment_address: Addr,
+    /// Share of the sale price, between 0 and 1
+    pub share: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingSend {
+    /// The owner the token is returned to if the receiver fails
+    pub owner: Addr,
+    /// The approvals the token had before it was sent
+    pub approvals: Vec<Approval>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingMinter {
+    /// The address that may accept the minter role
+    pub minter: Addr,
+    /// When the proposal can no longer be accepted (maybe Expiration::never)
+    pub expires: Expiration,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Metadata {
     pub image: String,
@@ -98,6 +562,29 @@ pub struct Metadata {
     pub youtube_url: Option<String>,
 }
 
+/// Bounds on the metadata stored with each token, lengths are in bytes
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct MetadataLimits {
+    /// Applies to every string field, attributes included
+    pub max_field_length: u32,
+    /// Applies to the JSON serialization of the whole metadata
+    pub max_metadata_size: u32,
+    pub max_attributes: u32,
+}
+
+impl Default for MetadataLimits {
+    fn default() -> Self {
+        MetadataLimits {
+            max_field_length: 2048,
+            max_metadata_size: 16384,
+            max_attributes: 50,
+        }
+    }
+}
+
+/// Schemes accepted for the URI fields of `Metadata`
+const URI_SCHEMES: [&str; 3] = ["ipfs://", "ar://", "https://"];
+
 impl Metadata {
     /// For easier testing, generate a value with dummy fields
     pub fn new_test() -> Self {
@@ -113,6 +600,111 @@ impl Metadata {
             youtube_url: None,
         }
     }
+
+    pub fn validate(&self, limits: &MetadataLimits) -> Result<(), ContractError> {
+        if self.attributes.len() > limits.max_attributes as usize {
+            return Err(ContractError::TooManyAttributes {
+                count: self.attributes.len(),
+                max: limits.max_attributes,
+            });
+        }
+
+        let mut fields = vec![
+            ("image", Some(&self.image)),
+            ("image_data", self.image_data.as_ref()),
+            ("external_url", self.external_url.as_ref()),
+            ("description", Some(&self.description)),
+            ("name", Some(&self.name)),
+            ("background_color", self.background_color.as_ref()),
+            ("animation_url", self.animation_url.as_ref()),
+            ("youtube_url", self.youtube_url.as_ref()),
+        ];
+        for attr in &self.attributes {
+            fields.push(("attributes.display_type", attr.display_type.as_ref()));
+            fields.push(("attributes.trait_type", Some(&attr.trait_type)));
+            fields.push(("attributes.value", Some(&attr.value)));
+        }
+        for (field, value) in fields {
+            if value.is_some_and(|value| value.len() > limits.max_field_length as usize) {
+                return Err(ContractError::FieldTooLong {
+                    field: field.to_owned(),
+                    max: limits.max_field_length,
+                });
+            }
+        }
+
+        // an empty image is allowed when the image is stored on chain in `image_data`
+        let uris = [
+            ("image", (!self.image.is_empty()).then_some(&self.image)),
+            ("external_url", self.external_url.as_ref()),
+            ("animation_url", self.animation_url.as_ref()),
+            ("youtube_url", self.youtube_url.as_ref()),
+        ];
+        for (field, uri) in uris {
+            if let Some(uri) = uri {
+                let valid = URI_SCHEMES.iter().any(|scheme| {
+                    uri.strip_prefix(scheme)
+                        .is_some_and(|rest| !rest.is_empty())
+                });
+                if !valid {
+                    return Err(ContractError::InvalidUri {
+                        field: field.to_owned(),
+                        uri: uri.clone(),
+                    });
+                }
+            }
+        }
+
+        if let Some(color) = &self.background_color {
+            if color.len() != 6 || !color.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(ContractError::InvalidBackgroundColor {
+                    color: color.clone(),
+                });
+            }
+        }
+
+        let size = to_vec(self)?.len();
+        if size > limits.max_metadata_size as usize {
+            return Err(ContractError::MetadataTooLarge {
+                size,
+                max: limits.max_metadata_size,
+            });
+        }
+        Ok(())
+    }
+
+    /// Names of the fields that differ between `self` and `other`
+    pub fn changed_fields(&self, other: &Metadata) -> Vec<&'static str> {
+        let mut changed = vec![];
+        if self.image != other.image {
+            changed.push("image");
+        }
+        if self.image_data != other.image_data {
+            changed.push("image_data");
+        }
+        if self.external_url != other.external_url {
+            changed.push("external_url");
+        }
+        if self.description != other.description {
+            changed.push("description");
+        }
+        if self.name != other.name {
+            changed.push("name");
+        }
+        if self.attributes != other.attributes {
+            changed.push("attributes");
+        }
+        if self.background_color != other.background_color {
+            changed.push("background_color");
+        }
+        if self.animation_url != other.animation_url {
+            changed.push("animation_url");
+        }
+        if self.youtube_url != other.youtube_url {
+            changed.push("youtube_url");
+        }
+        changed
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
@@ -122,6 +714,122 @@ pub struct Trait {
     pub value: String,
 }
 
+/// Attributes a collection accepts, any trait type without a rule is rejected
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TraitSchema {
+    pub traits: Vec<TraitRule>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TraitRule {
+    pub trait_type: String,
+    /// Whether every token has to carry this trait
+    pub required: bool,
+    pub values: ValueRule,
+    /// If set, the trait must use one of these display types
+    pub display_types: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueRule {
+    /// Any string
+    Any {},
+    /// One of the listed values
+    OneOf { values: Vec<String> },
+    /// A decimal number such as `-1.5`
+    Number {},
+    /// A unix timestamp in seconds
+    Date {},
+}
+
+impl TraitSchema {
+    /// Rejects schemas with more than one rule for the same trait type
+    pub fn check(&self) -> StdResult<()> {
+        for (i, rule) in self.traits.iter().enumerate() {
+            if self.traits[..i]
+                .iter()
+                .any(|other| other.trait_type == rule.trait_type)
+            {
+                return Err(StdError::generic_err(format!(
+                    "Duplicate rule for trait {}",
+                    rule.trait_type
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn validate(&self, attributes: &[Trait]) -> Result<(), ContractError> {
+        let invalid = |trait_type: &str, reason: String| ContractError::InvalidTrait {
+            trait_type: trait_type.to_owned(),
+            reason,
+        };
+        for (index, attr) in attributes.iter().enumerate() {
+            if attributes[..index]
+                .iter()
+                .any(|prev| prev.trait_type == attr.trait_type)
+            {
+                return Err(invalid(&attr.trait_type, "duplicate trait".to_owned()));
+            }
+            let rule = self
+                .traits
+                .iter()
+                .find(|rule| rule.trait_type == attr.trait_type)
+                .ok_or_else(|| invalid(&attr.trait_type, "unknown trait type".to_owned()))?;
+            let valid = match &rule.values {
+                ValueRule::Any {} => true,
+                ValueRule::OneOf { values } => values.contains(&attr.value),
+                ValueRule::Number {} => is_number(&attr.value),
+                ValueRule::Date {} => {
+                    !attr.value.is_empty() && attr.value.bytes().all(|b| b.is_ascii_digit())
+                }
+            };
+            if !valid {
+                return Err(invalid(
+                    &attr.trait_type,
+                    format!("value {:?} is not allowed", attr.value),
+                ));
+            }
+            if let Some(display_types) = &rule.display_types {
+                let allowed = attr
+                    .display_type
+                    .as_ref()
+                    .is_some_and(|display_type| display_types.contains(display_type));
+                if !allowed {
+                    return Err(invalid(
+                        &attr.trait_type,
+                        format!("display type {:?} is not allowed", attr.display_type),
+                    ));
+                }
+            }
+        }
+        for rule in self.traits.iter().filter(|rule| rule.required) {
+            if !attributes
+                .iter()
+                .any(|attr| attr.trait_type == rule.trait_type)
+            {
+                return Err(invalid(
+                    &rule.trait_type,
+                    "required trait is missing".to_owned(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Accepts an optional sign, digits and an optional fraction, without using floats
+fn is_number(value: &str) -> bool {
+    let value = value.strip_prefix('-').unwrap_or(value);
+    let (whole, fraction) = match value.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (value, None),
+    };
+    let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    digits(whole) && fraction.is_none_or(digits)
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct Approval {
     /// Account that can transfer/send the token
@@ -148,6 +856,12 @@ impl<'a> IndexList<TokenInfo> for TokenIndexes<'a> {
     }
 }
 
+fn token_indexes<'a>() -> TokenIndexes<'a> {
+    TokenIndexes {
+        owner: MultiIndex::new(token_owner_idx, TOKENS_KEY, TOKENS_OWNER_KEY),
+    }
+}
+
 pub fn token_owner_idx(d: &TokenInfo, k: Vec<u8>) -> (Addr, Vec<u8>) {
     (d.owner.clone(), k)
 }
@@ -1,3 +1,4 @@
+use std::convert::TryInto;
 use std::fmt::Display;
 
 use cosmwasm_std::{StdError, StdResult};
@@ -19,9 +20,18 @@ impl TokenId {
     pub fn new(value: u64) -> Self {
         TokenId {
             value,
-            bytes: value.to_le_bytes(),
+            bytes: value.to_be_bytes(),
         }
     }
+
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// The following ID, or `None` if this is the largest possible ID
+    pub fn next(&self) -> Option<TokenId> {
+        self.value.checked_add(1).map(TokenId::new)
+    }
 }
 
 impl Display for TokenId {
@@ -103,28 +113,34 @@ impl JsonSchema for TokenId {
 impl TokenId {
     /// Deserialize from the internal representation
     pub fn from_bytes(bytes: &[u8]) -> StdResult<TokenId> {
-        match hydrate_trailing_zeros(bytes) {
-            None => Err(StdError::serialize_err(
+        let bytes: Result<[u8; 8], _> = bytes.try_into();
+        match bytes {
+            Err(_) => Err(StdError::serialize_err(
                 "Token ID",
                 "Tokens must be exactly 8 bytes",
             )),
-            Some(arr) => Ok(TokenId::new(u64::from_le_bytes(arr))),
+            Ok(arr) => Ok(TokenId::new(u64::from_be_bytes(arr))),
         }
     }
 
     /// Serialize to the internal representation
     ///
-    /// To save storage space, removes trailing zeroes
+    /// Big-endian, so that storage keys sort in numeric order
     pub fn to_bytes(&self) -> &[u8] {
-        strip_trailing_zeros(&self.bytes)
+        &self.bytes
     }
-}
 
-fn strip_trailing_zeros(mut slice: &[u8]) -> &[u8] {
-    while slice.last() == Some(&0) {
-        slice = &slice[..slice.len() - 1];
+    /// Deserialize from the representation used by older versions, which was
+    /// little-endian with trailing zeroes removed
+    pub fn from_legacy_bytes(bytes: &[u8]) -> StdResult<TokenId> {
+        match hydrate_trailing_zeros(bytes) {
+            None => Err(StdError::serialize_err(
+                "Token ID",
+                "Tokens must be at most 8 bytes",
+            )),
+            Some(arr) => Ok(TokenId::new(u64::from_le_bytes(arr))),
+        }
     }
-    slice
 }
 
 /// Returns `None` if given a slice with more than 8 values
@@ -147,12 +163,47 @@ mod tests {
         fn bytes_round_trip(value: u64) -> bool {
             let token = TokenId::new(value);
             let bytes = token.to_bytes();
-            let token2 = TokenId::from_bytes(&bytes).unwrap();
+            let token2 = TokenId::from_bytes(bytes).unwrap();
             assert_eq!(token, token2);
             true
         }
     }
 
+    quickcheck! {
+        fn bytes_sort_numerically(a: u64, b: u64) -> bool {
+            let (a, b) = (TokenId::new(a), TokenId::new(b));
+            a.cmp(&b) == a.to_bytes().cmp(b.to_bytes())
+        }
+    }
+
+    #[test]
+    fn bytes_sort_across_byte_boundaries() {
+        let two = TokenId::new(2);
+        let big = TokenId::new(256);
+        assert!(two.to_bytes() < big.to_bytes());
+    }
+
+    #[test]
+    fn from_bytes_rejects_short_keys() {
+        TokenId::from_bytes(&[1]).unwrap_err();
+    }
+
+    quickcheck! {
+        fn legacy_bytes_round_trip(value: u64) -> bool {
+            let legacy = value.to_le_bytes();
+            let token = TokenId::from_legacy_bytes(strip_trailing_zeros(&legacy)).unwrap();
+            assert_eq!(TokenId::new(value), token);
+            true
+        }
+    }
+
+    fn strip_trailing_zeros(mut slice: &[u8]) -> &[u8] {
+        while slice.last() == Some(&0) {
+            slice = &slice[..slice.len() - 1];
+        }
+        slice
+    }
+
     #[test]
     fn strip_handles_empty_list() {
         let expected: &[u8] = &[];
@@ -169,7 +220,7 @@ mod tests {
         fn strip_hydrate_roundtrip(input: u64) -> bool {
             let input = input.to_le_bytes();
             let stripped = strip_trailing_zeros(&input);
-            let hydrated = hydrate_trailing_zeros(&stripped).unwrap();
+            let hydrated = hydrate_trailing_zeros(stripped).unwrap();
             assert_eq!(input, hydrated);
             true
         }